@@ -14,6 +14,32 @@ interface RustTrackInfo {
   cover_url: string | null;
 }
 
+// Matches Rust AudioError; `kind` tells the UI which recovery action fits
+export type AudioErrorKind =
+  | 'network'
+  | 'http_status'
+  | 'decode'
+  | 'unsupported_format'
+  | 'file_not_found'
+  | 'file_unreadable'
+  | 'device'
+  | 'seek'
+  | 'nothing_loaded'
+  | 'cancelled'
+  | 'timeout'
+  | 'engine_unavailable';
+
+export interface AudioError {
+  kind: AudioErrorKind;
+  message: string;
+  status?: number;
+  path?: string;
+}
+
+// A request superseded by a newer one isn't a failure: the newer request
+// reports its own outcome, so a cancellation never replaces the shown state
+const isCancelled = (err: AudioError | null) => err?.kind === 'cancelled';
+
 interface RustAudioState {
   current_track: RustTrackInfo | null;
   is_playing: boolean;
@@ -22,7 +48,7 @@ interface RustAudioState {
   volume: number;
   is_muted: boolean;
   is_loading: boolean;
-  error: string | null;
+  error: AudioError | null;
 }
 
 // Frontend state (matches original interface)
@@ -34,7 +60,7 @@ interface AudioState {
   volume: number;
   isMuted: boolean;
   isLoading: boolean;
-  error: string | null;
+  error: AudioError | null;
 }
 
 interface AudioContextType {
@@ -135,7 +161,7 @@ export function AudioProvider({ children }: { children: ReactNode }) {
           volume: rustState.volume,
           isMuted: rustState.is_muted,
           isLoading: rustState.is_loading,
-          error: rustState.error,
+          error: isCancelled(rustState.error) ? s.error : rustState.error,
         }));
       })
     );
@@ -156,7 +182,10 @@ export function AudioProvider({ children }: { children: ReactNode }) {
 
     // Track ended event - handle auto-advance
     unlisteners.push(
-      listen<{ track_id: string }>('audio:track-ended', () => {
+      listen<{ track_id: string }>('audio:track-ended', (event) => {
+        // Tracks started elsewhere (e.g. from an MPD client's queue) are
+        // advanced by whoever started them
+        if (event.payload.track_id !== currentTrackRef.current?.id) return;
         // This is handled by the handleTrackEnded function below
         handleTrackEndedRef.current?.();
       })
@@ -172,7 +201,7 @@ export function AudioProvider({ children }: { children: ReactNode }) {
         volume: rustState.volume,
         isMuted: rustState.is_muted,
         isLoading: rustState.is_loading,
-        error: rustState.error,
+        error: isCancelled(rustState.error) ? s.error : rustState.error,
       }));
     }).catch(() => {
       // Audio engine not ready yet, that's OK
@@ -199,8 +228,9 @@ export function AudioProvider({ children }: { children: ReactNode }) {
     invoke('audio_play_track', {
       track: rustTrack,
       sourceUrl: streamUrl,
-    }).catch((err) => {
-      setState(s => ({ ...s, error: String(err), isLoading: false }));
+    }).catch((err: AudioError) => {
+      if (isCancelled(err)) return;
+      setState(s => ({ ...s, error: err, isLoading: false }));
     });
   }, [api]);
 
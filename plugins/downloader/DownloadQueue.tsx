@@ -12,15 +12,51 @@ interface DownloadProgress {
   track_name: string;
   status: string;
   error: string | null;
+  matched?: TrackMatch | null;
+  progress?: TrackProgress | null;
+}
+
+interface TrackProgress {
+  downloaded_bytes: number;
+  total_bytes: number | null;
+  percent: number | null;
+  speed: number | null;
+  eta_secs: number | null;
+}
+
+interface TrackMatch {
+  id: string;
+  title: string;
+  channel: string;
+  duration: number | null;
+  score: number;
 }
 
 interface ActiveTrack {
   track_index: number;
   track_name: string;
   status: string;
+  matched?: TrackMatch | null;
+  progress?: TrackProgress | null;
+}
+
+interface TrackState {
+  track_index: number;
+  title: string;
+  disc: number;
+  track: number;
+  status: string;
+  error: string | null;
+}
+
+interface DiscProgress {
+  disc: number;
+  completed_tracks: number;
+  total_tracks: number;
 }
 
 interface AlbumState {
+  id: number;
   artist: string;
   album: string;
   status: string;
@@ -30,23 +66,50 @@ interface AlbumState {
   currentTrack?: string;
   currentTrackStatus?: string;
   active_tracks: ActiveTrack[];
+  discs?: DiscProgress[];
+  tracks?: TrackState[];
+  downloaded_bytes?: number;
+  total_bytes?: number;
+  speed?: number;
+  eta_secs?: number | null;
 }
 
+const formatBytes = (n: number) => {
+  if (n >= 1024 * 1024 * 1024) return `${(n / (1024 * 1024 * 1024)).toFixed(1)} GB`;
+  if (n >= 1024 * 1024) return `${(n / (1024 * 1024)).toFixed(1)} MB`;
+  return `${Math.round(n / 1024)} KB`;
+};
+
+const formatEta = (secs: number) => {
+  const h = Math.floor(secs / 3600);
+  const m = Math.floor((secs % 3600) / 60);
+  const s = secs % 60;
+  return h > 0 ? `${h}h ${m}m` : `${m}:${String(s).padStart(2, '0')}`;
+};
+
 interface DownloadQueueProps {
   onToast: (msg: string) => void;
-  onAllComplete: () => void;
 }
 
-export const DownloadQueue: React.FC<DownloadQueueProps> = ({ onToast, onAllComplete }) => {
+export const DownloadQueue: React.FC<DownloadQueueProps> = ({ onToast }) => {
   const [albums, setAlbums] = useState<AlbumState[]>([]);
   const [isActive, setIsActive] = useState(false);
+  const [queueEta, setQueueEta] = useState<number | null>(null);
+  const [windowOpensAt, setWindowOpensAt] = useState<string | null>(null);
 
   // Poll state on mount
   useEffect(() => {
     const poll = async () => {
       try {
-        const state = await invoke<{ is_active: boolean; albums: AlbumState[] }>('downloader_get_status');
+        const state = await invoke<{
+          is_active: boolean;
+          albums: AlbumState[];
+          eta_secs: number | null;
+          waiting_for_window?: string | null;
+        }>('downloader_get_status');
         setIsActive(state.is_active);
+        setQueueEta(state.eta_secs);
+        setWindowOpensAt(state.waiting_for_window ?? null);
         if (state.albums.length > 0) {
           setAlbums(prev => {
             const updated = [...state.albums];
@@ -80,7 +143,7 @@ export const DownloadQueue: React.FC<DownloadQueueProps> = ({ onToast, onAllComp
         if (next[p.album_index]) {
           next[p.album_index] = {
             ...next[p.album_index],
-            completed_tracks: p.status === 'done'
+            completed_tracks: p.status === 'done' || p.status === 'owned'
               ? next[p.album_index].completed_tracks + 1
               : next[p.album_index].completed_tracks,
             total_tracks: p.total_tracks || next[p.album_index].total_tracks,
@@ -101,7 +164,6 @@ export const DownloadQueue: React.FC<DownloadQueueProps> = ({ onToast, onAllComp
     listen('download-all-complete', () => {
       setIsActive(false);
       onToast('All downloads complete!');
-      onAllComplete();
     }).then(u => unlisteners.push(u));
 
     listen('download-cancelled', () => {
@@ -109,13 +171,17 @@ export const DownloadQueue: React.FC<DownloadQueueProps> = ({ onToast, onAllComp
       onToast('Downloads cancelled');
     }).then(u => unlisteners.push(u));
 
+    listen('download-waiting', (event: any) => {
+      setWindowOpensAt(event.payload.opensAt);
+    }).then(u => unlisteners.push(u));
+
     listen('download-error', (event: any) => {
       const { artist, album, error } = event.payload;
       onToast(`Error: ${artist} - ${album}: ${error}`);
     }).then(u => unlisteners.push(u));
 
     return () => { unlisteners.forEach(u => u()); };
-  }, [onToast, onAllComplete]);
+  }, [onToast]);
 
   const handleCancel = async () => {
     try {
@@ -125,6 +191,24 @@ export const DownloadQueue: React.FC<DownloadQueueProps> = ({ onToast, onAllComp
     }
   };
 
+  const handleResume = async () => {
+    try {
+      await invoke('downloader_resume', { ids: null });
+      setIsActive(true);
+    } catch (e: any) {
+      onToast(`Resume failed: ${e}`);
+    }
+  };
+
+  const itemAction = async (label: string, command: string, args: Record<string, unknown>) => {
+    try {
+      await invoke(command, args);
+      if (command === 'downloader_resume' || command === 'downloader_retry_failed') setIsActive(true);
+    } catch (e: any) {
+      onToast(`${label} failed: ${e}`);
+    }
+  };
+
   const handleClearFinished = useCallback(async () => {
     try {
       await invoke('downloader_clear_finished');
@@ -147,16 +231,21 @@ export const DownloadQueue: React.FC<DownloadQueueProps> = ({ onToast, onAllComp
     a.status === 'pending' || a.status === 'downloading'
   ).length;
 
+  const hasPaused = albums.some(a => a.status === 'paused');
+
   const statusLabel = (s: string) => {
     switch (s) {
       case 'searching': return 'Searching YouTube...';
       case 'downloading': return 'Downloading...';
       case 'tagging': return 'Tagging...';
+      case 'fetching_lyrics': return 'Fetching lyrics...';
       case 'done': return 'Done';
+      case 'owned': return 'Already owned';
       case 'complete': return 'Complete';
       case 'error': return 'Error';
       case 'cancelled': return 'Cancelled';
       case 'pending': return 'Waiting...';
+      case 'paused': return 'Paused';
       case 'fetching_cover': return 'Fetching cover art...';
       case 'fetching_tracklist': return 'Fetching tracklist...';
       default: return s;
@@ -169,6 +258,7 @@ export const DownloadQueue: React.FC<DownloadQueueProps> = ({ onToast, onAllComp
       case 'error': return 'text-red-400';
       case 'cancelled': return 'text-orange-400';
       case 'downloading': return 'text-blue-400';
+      case 'paused': return 'text-yellow-400';
       default: return 'text-white/50';
     }
   };
@@ -179,6 +269,7 @@ export const DownloadQueue: React.FC<DownloadQueueProps> = ({ onToast, onAllComp
       case 'error': return 'bg-red-400';
       case 'cancelled': return 'bg-orange-400';
       case 'downloading': return 'bg-blue-400 animate-pulse';
+      case 'paused': return 'bg-yellow-400';
       case 'pending': return 'bg-white/20';
       default: return 'bg-white/20';
     }
@@ -192,8 +283,19 @@ export const DownloadQueue: React.FC<DownloadQueueProps> = ({ onToast, onAllComp
           {activeCount > 0 && (
             <span className="ml-2 text-white/60">{activeCount} active</span>
           )}
+          {activeCount > 0 && queueEta != null && (
+            <span className="ml-2 text-white/30 normal-case tracking-normal">~{formatEta(queueEta)} left</span>
+          )}
         </h3>
         <div className="flex items-center gap-3">
+          {hasPaused && (
+            <button
+              onClick={handleResume}
+              className="text-xs text-yellow-400/70 hover:text-yellow-400 transition-colors font-medium tracking-wide"
+            >
+              Resume
+            </button>
+          )}
           {hasFinished && (
             <button
               onClick={handleClearFinished}
@@ -213,13 +315,24 @@ export const DownloadQueue: React.FC<DownloadQueueProps> = ({ onToast, onAllComp
         </div>
       </div>
 
+      {windowOpensAt && (
+        <p className="text-xs text-white/40">
+          Outside the download window — queued items start at {windowOpensAt}
+        </p>
+      )}
+
       {albums.map((a, i) => {
         const progress = a.total_tracks > 0 ? (a.completed_tracks / a.total_tracks) * 100 : 0;
         const isFinished = a.status === 'complete' || a.status === 'error' || a.status === 'cancelled';
+        const failedTracks = (a.tracks || []).filter(t => t.status === 'error');
+        const canRetry = (a.status === 'complete' && failedTracks.length > 0)
+          || (a.status === 'error' && (failedTracks.length > 0 || !a.tracks?.length));
+        const prevPending = albums.slice(0, i).some(b => b.status === 'pending');
+        const nextPending = albums.slice(i + 1).some(b => b.status === 'pending');
 
         return (
           <div
-            key={`${a.artist}-${a.album}-${i}`}
+            key={a.id}
             className={`bg-white/[0.03] border border-white/5 rounded-2xl p-5 space-y-3 transition-opacity ${
               isFinished ? 'opacity-50' : ''
             }`}
@@ -230,11 +343,35 @@ export const DownloadQueue: React.FC<DownloadQueueProps> = ({ onToast, onAllComp
                 <div className="text-sm font-semibold truncate">{a.album}</div>
                 <div className="text-xs text-white/40 truncate">{a.artist}</div>
               </div>
-              <div className="flex items-center gap-2 flex-shrink-0">
-                <div className={`w-2 h-2 rounded-full ${statusDot(a.status)}`} />
-                <span className={`text-xs font-medium capitalize ${statusColor(a.status)}`}>
-                  {statusLabel(a.status)}
-                </span>
+              <div className="flex flex-col items-end gap-1.5 flex-shrink-0">
+                <div className="flex items-center gap-2">
+                  <div className={`w-2 h-2 rounded-full ${statusDot(a.status)}`} />
+                  <span className={`text-xs font-medium capitalize ${statusColor(a.status)}`}>
+                    {statusLabel(a.status)}
+                  </span>
+                </div>
+                <div className="flex items-center gap-2 text-[10px] font-medium tracking-wide">
+                  {a.status === 'pending' && prevPending && (
+                    <button onClick={() => itemAction('Move', 'downloader_move_item', { id: a.id, offset: -1 })} className="text-white/30 hover:text-white/70 transition-colors" title="Move up">▲</button>
+                  )}
+                  {a.status === 'pending' && nextPending && (
+                    <button onClick={() => itemAction('Move', 'downloader_move_item', { id: a.id, offset: 1 })} className="text-white/30 hover:text-white/70 transition-colors" title="Move down">▼</button>
+                  )}
+                  {(a.status === 'pending' || a.status === 'downloading') && (
+                    <button onClick={() => itemAction('Pause', 'downloader_pause_item', { id: a.id })} className="text-yellow-400/60 hover:text-yellow-400 transition-colors">Pause</button>
+                  )}
+                  {a.status === 'paused' && (
+                    <button onClick={() => itemAction('Resume', 'downloader_resume', { ids: [a.id] })} className="text-yellow-400/60 hover:text-yellow-400 transition-colors">Resume</button>
+                  )}
+                  {(a.status === 'pending' || a.status === 'downloading' || a.status === 'paused') && (
+                    <button onClick={() => itemAction('Cancel', 'downloader_cancel_item', { id: a.id })} className="text-red-400/60 hover:text-red-400 transition-colors">Cancel</button>
+                  )}
+                  {canRetry && (
+                    <button onClick={() => itemAction('Retry', 'downloader_retry_failed', { id: a.id })} className="text-blue-400/70 hover:text-blue-400 transition-colors">
+                      {failedTracks.length > 0 ? `Retry ${failedTracks.length} failed` : 'Retry'}
+                    </button>
+                  )}
+                </div>
               </div>
             </div>
 
@@ -250,9 +387,23 @@ export const DownloadQueue: React.FC<DownloadQueueProps> = ({ onToast, onAllComp
                 <div className="flex items-center justify-between text-[10px] text-white/30 font-mono">
                   <span>
                     {a.completed_tracks}/{a.total_tracks} tracks
+                    {a.status === 'downloading' && !!a.total_bytes && (
+                      <> · {formatBytes(a.downloaded_bytes || 0)} / ~{formatBytes(a.total_bytes)}</>
+                    )}
+                    {a.status === 'downloading' && !!a.speed && <> · {formatBytes(a.speed)}/s</>}
+                    {a.status === 'downloading' && a.eta_secs != null && <> · {formatEta(a.eta_secs)}</>}
                   </span>
                   <span>{Math.round(progress)}%</span>
                 </div>
+                {a.discs && a.discs.length > 1 && (
+                  <div className="flex flex-wrap gap-x-3 text-[10px] text-white/30 font-mono">
+                    {a.discs.map((d) => (
+                      <span key={d.disc}>
+                        Disc {d.disc}: {d.completed_tracks}/{d.total_tracks}
+                      </span>
+                    ))}
+                  </div>
+                )}
               </div>
             )}
 
@@ -262,8 +413,19 @@ export const DownloadQueue: React.FC<DownloadQueueProps> = ({ onToast, onAllComp
                 {a.active_tracks.map((at) => (
                   <div key={at.track_index} className="text-[11px] text-white/30 truncate">
                     <span className="text-white/50">{statusLabel(at.status)}</span>
+                    {at.progress?.percent != null && (
+                      <span className="text-white/50 font-mono"> {Math.round(at.progress.percent)}%</span>
+                    )}
                     {' '}
                     {at.track_name}
+                    {at.matched && (
+                      <span
+                        className="text-white/20"
+                        title={`${at.matched.title} (score ${at.matched.score.toFixed(1)})`}
+                      >
+                        {' '}· {at.matched.channel}
+                      </span>
+                    )}
                   </div>
                 ))}
               </div>
@@ -277,6 +439,17 @@ export const DownloadQueue: React.FC<DownloadQueueProps> = ({ onToast, onAllComp
               </div>
             )}
 
+            {/* Failed tracks */}
+            {failedTracks.length > 0 && a.status !== 'downloading' && (
+              <div className="space-y-0.5">
+                {failedTracks.map(t => (
+                  <div key={t.track_index} className="text-[11px] text-red-400/60 truncate">
+                    {t.track}. {t.title}{t.error ? ` — ${t.error}` : ''}
+                  </div>
+                ))}
+              </div>
+            )}
+
             {/* Error */}
             {a.error && (
               <div className="text-[11px] text-red-400/70 truncate">
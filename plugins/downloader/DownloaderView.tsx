@@ -1,8 +1,10 @@
 import React, { useState, useCallback, useRef, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { PluginViewProps } from '../../types';
 import { useServer } from '../../context/ServerContext';
 import { DownloadQueue } from './DownloadQueue';
+import { ImportView } from './ImportView';
 
 interface MbArtist {
   id: string;
@@ -16,6 +18,55 @@ interface MbAlbum {
   year: string;
   type: string;
   secondary_types: string[];
+  release_date: string;
+}
+
+interface MbRelease {
+  id: string;
+  title: string;
+  disambiguation: string;
+  status: string;
+  date: string;
+  country: string;
+  format: string;
+  disc_count: number;
+  track_count: number;
+}
+
+interface WatchedArtist {
+  id: string;
+  name: string;
+  genre: string;
+  seen: string[];
+  last_checked: number | null;
+}
+
+interface NewRelease {
+  artist_id: string;
+  artist: string;
+  album: MbAlbum;
+  queued: boolean;
+}
+
+interface OwnedTrack {
+  track_index: number;
+  title: string;
+  disc: number;
+  track: number;
+  paths: string[];
+}
+
+type DuplicatePolicy = 'skip' | 'replace' | 'keep_both';
+
+interface AlbumRequest {
+  artist: string;
+  album: string;
+  year: string;
+  genre: string;
+  tracks: null;
+  release_group_id?: string;
+  release_id?: string | null;
+  duplicate_policy?: DuplicatePolicy;
 }
 
 interface ManualAlbum {
@@ -41,7 +92,7 @@ interface SongEntry {
   genre: string;
 }
 
-type Tab = 'search' | 'songs' | 'manual';
+type Tab = 'search' | 'songs' | 'manual' | 'import';
 type TypeFilter = 'all' | 'album' | 'ep' | 'single' | 'other';
 
 // ── Lazy loading image component ──────────────────────────────────────────
@@ -99,6 +150,16 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
   const [genreOverride, setGenreOverride] = useState('');
   const [typeFilter, setTypeFilter] = useState<TypeFilter>('all');
   const [hideCompilations, setHideCompilations] = useState(true);
+  // Editions per release group, loaded when an album is selected
+  const [editions, setEditions] = useState<Record<string, MbRelease[]>>({});
+  const [chosenEdition, setChosenEdition] = useState<Record<string, string>>({});
+
+  // ── Followed artists ───────────────────────────────────────────────────
+  const [watchlist, setWatchlist] = useState<WatchedArtist[]>([]);
+  const [isFollowing, setIsFollowing] = useState(false);
+  const [isCheckingWatchlist, setIsCheckingWatchlist] = useState(false);
+  // Scheduled checks finish with the same event; only a manual one reports "nothing new"
+  const manualCheckRef = useRef(false);
 
   // ── Manual mode state ──────────────────────────────────────────────────
   const [manualEntries, setManualEntries] = useState<ManualAlbum[]>([
@@ -115,6 +176,10 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
   // ── Download state (tracks whether we just submitted, for brief button feedback) ──
   const [justSubmitted, setJustSubmitted] = useState(false);
 
+  // ── Already-owned check before queueing selected albums ───────────────
+  const [isCheckingOwned, setIsCheckingOwned] = useState(false);
+  const [ownedPrompt, setOwnedPrompt] = useState<{ albums: AlbumRequest[]; owned: OwnedTrack[] } | null>(null);
+
   // ── Search for artists ─────────────────────────────────────────────────
   const handleSearch = useCallback(async () => {
     const query = searchInput.trim();
@@ -148,6 +213,8 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
     setIsLoadingDiscography(true);
     setDiscography([]);
     setSelected(new Set());
+    setEditions({});
+    setChosenEdition({});
 
     try {
       const albums = await invoke<MbAlbum[]>('downloader_get_discography', { artistId: artist.id });
@@ -159,6 +226,62 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
     }
   }, [onToast]);
 
+  // ── Follow artists for new releases ────────────────────────────────────
+  useEffect(() => {
+    invoke<WatchedArtist[]>('downloader_get_watchlist').then(setWatchlist).catch(() => {});
+
+    const unlisteners: (() => void)[] = [];
+    listen<NewRelease>('downloader:new-release', (event) => {
+      const { artist, album, queued } = event.payload;
+      onToast(`New release: ${artist} - ${album.title}${queued ? ' (queued)' : ''}`);
+    }).then(u => unlisteners.push(u));
+    listen<{ artists: number; newReleases: number }>('downloader:watchlist-checked', (event) => {
+      if (manualCheckRef.current && event.payload.newReleases === 0) onToast('No new releases');
+      manualCheckRef.current = false;
+      setIsCheckingWatchlist(false);
+      invoke<WatchedArtist[]>('downloader_get_watchlist').then(setWatchlist).catch(() => {});
+    }).then(u => unlisteners.push(u));
+
+    return () => { unlisteners.forEach(u => u()); };
+  }, [onToast]);
+
+  const isFollowed = (artistId: string) => watchlist.some(a => a.id === artistId);
+
+  const toggleFollow = async (artist: MbArtist) => {
+    setIsFollowing(true);
+    try {
+      if (isFollowed(artist.id)) {
+        await invoke('downloader_unfollow_artist', { artistId: artist.id });
+        setWatchlist(prev => prev.filter(a => a.id !== artist.id));
+        onToast(`Unfollowed ${artist.name}`);
+      } else {
+        const followed = await invoke<WatchedArtist>('downloader_follow_artist', {
+          artistId: artist.id,
+          name: artist.name,
+          genre: genreOverride || null,
+        });
+        setWatchlist(prev => [...prev, followed]);
+        onToast(`Following ${artist.name}: new releases will be reported`);
+      }
+    } catch (e: any) {
+      onToast(`Failed to update followed artists: ${e}`);
+    } finally {
+      setIsFollowing(false);
+    }
+  };
+
+  const handleCheckWatchlist = async () => {
+    setIsCheckingWatchlist(true);
+    manualCheckRef.current = true;
+    try {
+      await invoke('downloader_check_watchlist');
+    } catch (e: any) {
+      manualCheckRef.current = false;
+      setIsCheckingWatchlist(false);
+      onToast(`Check failed: ${e}`);
+    }
+  };
+
   // ── Filter discography ─────────────────────────────────────────────────
   const filteredDiscography = discography.filter(a => {
     // Hide compilations/live/soundtrack if toggle is on
@@ -181,14 +304,25 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
 
   // ── Toggle album selection ─────────────────────────────────────────────
   const toggleSelect = (id: string) => {
+    const selecting = !selected.has(id);
     setSelected(prev => {
       const next = new Set(prev);
       if (next.has(id)) next.delete(id);
       else next.add(id);
       return next;
     });
+    if (selecting && !editions[id]) {
+      invoke<MbRelease[]>('downloader_get_releases', { releaseGroupId: id })
+        .then(releases => setEditions(prev => ({ ...prev, [id]: releases })))
+        .catch(() => {});
+    }
   };
 
+  const editionLabel = (r: MbRelease) =>
+    [r.date || 'Undated', r.country, r.format, `${r.track_count} tracks`, r.disambiguation]
+      .filter(Boolean)
+      .join(' · ');
+
   const selectAll = () => {
     const filteredIds = filteredDiscography.map(a => a.id);
     if (filteredIds.every(id => selected.has(id))) {
@@ -215,16 +349,39 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
       return;
     }
 
-    const albums = toDownload.map(a => ({
+    const albums: AlbumRequest[] = toDownload.map(a => ({
       artist: selectedArtist.name,
       album: a.title,
       year: a.year,
       genre: genreOverride || 'Rock',
-      tracks: null as string[] | null,
+      tracks: null,
+      release_group_id: a.id,
+      // Unset means the backend picks the group's default edition
+      release_id: chosenEdition[a.id] ?? null,
     }));
 
+    // Look for tracks the library already has so the user can decide what to do
+    setIsCheckingOwned(true);
+    const owned: OwnedTrack[] = [];
+    for (const album of albums) {
+      try {
+        owned.push(...await invoke<OwnedTrack[]>('downloader_find_owned', { album }));
+      } catch {}
+    }
+    setIsCheckingOwned(false);
+
+    if (owned.length > 0) {
+      setOwnedPrompt({ albums, owned });
+    } else {
+      queueSelected(albums);
+    }
+  }, [discography, selected, selectedArtist, genreOverride, chosenEdition, onToast]);
+
+  const queueSelected = async (albums: AlbumRequest[], policy?: DuplicatePolicy) => {
+    setOwnedPrompt(null);
+    const requests = policy ? albums.map(a => ({ ...a, duplicate_policy: policy })) : albums;
     try {
-      await invoke('downloader_start', { albums });
+      await invoke('downloader_start', { albums: requests });
       onToast(`Queued ${albums.length} album${albums.length > 1 ? 's' : ''} for download`);
       setSelected(new Set());
       setJustSubmitted(true);
@@ -232,7 +389,7 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
     } catch (e: any) {
       onToast(`Download failed: ${e}`);
     }
-  }, [discography, selected, selectedArtist, genreOverride, onToast]);
+  };
 
   // ── Search for songs on YouTube ────────────────────────────────────────
   const handleSearchSongs = useCallback(async () => {
@@ -348,7 +505,7 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
       album: e.album.trim(),
       year: e.year.trim(),
       genre: e.genre.trim() || 'Rock',
-      tracks: null as string[] | null,
+      tracks: null,
     }));
 
     try {
@@ -362,25 +519,40 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
     }
   }, [manualEntries, onToast]);
 
-  // ── After all downloads complete ───────────────────────────────────────
-  const handleAllComplete = useCallback(async () => {
-    if (serverState.isConnected && serverState.serverUrl) {
-      try {
-        const savedConfig = localStorage.getItem('lumina-server-config');
-        if (savedConfig) {
-          const { url, username, password } = JSON.parse(savedConfig);
-          await invoke('downloader_trigger_scan', { serverUrl: url, username, password });
-          onToast('Navidrome rescan triggered');
-          setTimeout(() => {
-            refreshAlbums();
-            refreshArtists();
-          }, 3000);
-        }
-      } catch (e: any) {
-        onToast(`Rescan failed: ${e}`);
-      }
+  // ── Rescan the server after downloads and imports ──────────────────────
+  // The backend batches finished downloads into one scan of the connected
+  // server and reports when it is done.
+  useEffect(() => {
+    let server = null;
+    const savedConfig = localStorage.getItem('lumina-server-config');
+    if (serverState.isConnected && savedConfig) {
+      const { url, username, password } = JSON.parse(savedConfig);
+      server = { url, username, password };
     }
-  }, [serverState, onToast, refreshAlbums, refreshArtists]);
+    invoke('downloader_set_scan_server', { server }).catch(() => {});
+  }, [serverState.isConnected, serverState.serverUrl]);
+
+  useEffect(() => {
+    const unlisteners: (() => void)[] = [];
+    let scanning = false;
+    listen<{ count: number }>('downloader:scan-progress', () => {
+      if (!scanning) onToast('Navidrome rescan started');
+      scanning = true;
+    }).then(u => unlisteners.push(u));
+    listen<{ count: number; new_items: number }>('downloader:scan-complete', (event) => {
+      scanning = false;
+      const { new_items } = event.payload;
+      onToast(`Navidrome rescan finished: ${new_items} new item${new_items !== 1 ? 's' : ''}`);
+      refreshAlbums();
+      refreshArtists();
+    }).then(u => unlisteners.push(u));
+    listen<{ error: string }>('downloader:scan-failed', (event) => {
+      scanning = false;
+      onToast(`Rescan failed: ${event.payload.error}`);
+    }).then(u => unlisteners.push(u));
+
+    return () => { unlisteners.forEach(u => u()); };
+  }, [onToast, refreshAlbums, refreshArtists]);
 
   // ── Manual entry helpers ───────────────────────────────────────────────
   const updateManualEntry = (index: number, field: keyof ManualAlbum, value: string) => {
@@ -432,7 +604,7 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
         <h2 className="text-sm font-bold tracking-[0.2em] text-white/40 uppercase mb-2">Plugin</h2>
         <h1 className="text-4xl font-bold tracking-tight">Downloader</h1>
         <p className="text-sm text-white/30 mt-2">
-          Download albums by artist, individual songs, or enter manually. Auto-tagged with MusicBrainz metadata.
+          Download albums by artist or individual songs, enter them manually, or import music you already have. Auto-tagged with MusicBrainz metadata.
         </p>
       </div>
 
@@ -462,6 +634,14 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
         >
           Manual
         </button>
+        <button
+          onClick={() => setActiveTab('import')}
+          className={`px-5 py-2 rounded-lg text-sm font-medium transition-colors ${
+            activeTab === 'import' ? 'bg-white/10 text-white' : 'text-white/40 hover:text-white/70'
+          }`}
+        >
+          Import
+        </button>
       </div>
 
       <div className="flex gap-8 flex-col lg:flex-row">
@@ -538,6 +718,17 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
                       </button>
                       <h3 className="text-lg font-bold">{selectedArtist.name}</h3>
                       <span className="text-xs text-white/30">{discography.length} releases</span>
+                      <button
+                        onClick={() => toggleFollow(selectedArtist)}
+                        disabled={isFollowing}
+                        className={`px-3 py-1 rounded-lg text-xs font-medium border transition-colors disabled:opacity-30 ${
+                          isFollowed(selectedArtist.id)
+                            ? 'bg-white/10 border-white/10 text-white'
+                            : 'border-white/10 text-white/40 hover:text-white/70'
+                        }`}
+                      >
+                        {isFollowed(selectedArtist.id) ? 'Following' : 'Follow'}
+                      </button>
                     </div>
                     <button
                       onClick={selectAll}
@@ -595,47 +786,69 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
                   <div className="grid grid-cols-1 sm:grid-cols-2 gap-3">
                     {filteredDiscography.map(album => {
                       const isSelected = selected.has(album.id);
+                      const albumEditions = editions[album.id] || [];
                       return (
-                        <button
-                          key={album.id}
-                          onClick={() => toggleSelect(album.id)}
-                          className={`flex items-center gap-4 p-3 rounded-xl border text-left transition-all ${
-                            isSelected
-                              ? 'bg-white/10 border-purple-500/40 shadow-[0_0_20px_rgba(168,85,247,0.05)]'
-                              : 'bg-white/[0.02] border-white/5 hover:bg-white/5'
-                          }`}
-                        >
-                          <LazyImage
-                            src={getCoverUrl(album.id)}
-                            alt=""
-                            className="w-14 h-14 rounded-lg bg-white/5 flex-shrink-0 overflow-hidden"
-                          />
-                          <div className="min-w-0 flex-1">
-                            <div className="text-sm font-semibold truncate">{album.title}</div>
-                            <div className="text-xs text-white/30 flex items-center gap-2 mt-0.5">
-                              {album.year && <span>{album.year}</span>}
-                              {typeLabel(album.type) && (
-                                <span className="px-1.5 py-0.5 bg-white/5 rounded text-[10px] uppercase tracking-wider">
-                                  {typeLabel(album.type)}
-                                </span>
-                              )}
-                              {album.secondary_types.length > 0 && (
-                                <span className="text-[10px] text-white/20">
-                                  {album.secondary_types.join(', ')}
-                                </span>
+                        <div key={album.id} className="space-y-1.5">
+                          <button
+                            onClick={() => toggleSelect(album.id)}
+                            className={`w-full flex items-center gap-4 p-3 rounded-xl border text-left transition-all ${
+                              isSelected
+                                ? 'bg-white/10 border-purple-500/40 shadow-[0_0_20px_rgba(168,85,247,0.05)]'
+                                : 'bg-white/[0.02] border-white/5 hover:bg-white/5'
+                            }`}
+                          >
+                            <LazyImage
+                              src={getCoverUrl(album.id)}
+                              alt=""
+                              className="w-14 h-14 rounded-lg bg-white/5 flex-shrink-0 overflow-hidden"
+                            />
+                            <div className="min-w-0 flex-1">
+                              <div className="text-sm font-semibold truncate">{album.title}</div>
+                              <div className="text-xs text-white/30 flex items-center gap-2 mt-0.5">
+                                {album.year && <span>{album.year}</span>}
+                                {typeLabel(album.type) && (
+                                  <span className="px-1.5 py-0.5 bg-white/5 rounded text-[10px] uppercase tracking-wider">
+                                    {typeLabel(album.type)}
+                                  </span>
+                                )}
+                                {album.secondary_types.length > 0 && (
+                                  <span className="text-[10px] text-white/20">
+                                    {album.secondary_types.join(', ')}
+                                  </span>
+                                )}
+                              </div>
+                            </div>
+                            <div className={`w-5 h-5 rounded-md border-2 flex-shrink-0 flex items-center justify-center transition-colors ${
+                              isSelected ? 'bg-purple-500 border-purple-500' : 'border-white/20'
+                            }`}>
+                              {isSelected && (
+                                <svg width="12" height="12" viewBox="0 0 12 12" fill="none">
+                                  <path d="M2.5 6L5 8.5L9.5 3.5" stroke="white" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round"/>
+                                </svg>
                               )}
                             </div>
-                          </div>
-                          <div className={`w-5 h-5 rounded-md border-2 flex-shrink-0 flex items-center justify-center transition-colors ${
-                            isSelected ? 'bg-purple-500 border-purple-500' : 'border-white/20'
-                          }`}>
-                            {isSelected && (
-                              <svg width="12" height="12" viewBox="0 0 12 12" fill="none">
-                                <path d="M2.5 6L5 8.5L9.5 3.5" stroke="white" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round"/>
-                              </svg>
-                            )}
-                          </div>
-                        </button>
+                          </button>
+                          {isSelected && albumEditions.length > 1 && (
+                            <select
+                              value={chosenEdition[album.id] ?? ''}
+                              onChange={e => {
+                                const value = e.target.value;
+                                setChosenEdition(prev => {
+                                  const next = { ...prev };
+                                  if (value) next[album.id] = value;
+                                  else delete next[album.id];
+                                  return next;
+                                });
+                              }}
+                              className="w-full bg-white/5 border border-white/5 rounded-lg px-2 py-1.5 text-[11px] text-white/60 focus:outline-none focus:border-white/20"
+                            >
+                              <option value="">Default edition</option>
+                              {albumEditions.map(r => (
+                                <option key={r.id} value={r.id}>{editionLabel(r)}</option>
+                              ))}
+                            </select>
+                          )}
+                        </div>
                       );
                     })}
                   </div>
@@ -646,14 +859,62 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
                     </div>
                   )}
 
+                  {/* Already-owned prompt */}
+                  {ownedPrompt && (
+                    <div className="pt-4 space-y-3">
+                      <div className="bg-white/[0.03] border border-yellow-400/20 rounded-xl p-4 space-y-2">
+                        <div className="text-sm font-semibold text-yellow-400/80">
+                          {`${ownedPrompt.owned.length} track${ownedPrompt.owned.length > 1 ? 's' : ''} already owned`}
+                        </div>
+                        <div className="max-h-32 overflow-y-auto space-y-0.5">
+                          {ownedPrompt.owned.map((t, i) => (
+                            <div key={i} className="text-[11px] text-white/40 truncate" title={t.paths.join('\n')}>
+                              {t.title}
+                              <span className="text-white/20"> · {t.paths[0]}</span>
+                            </div>
+                          ))}
+                        </div>
+                      </div>
+                      <div className="flex gap-2">
+                        <button
+                          onClick={() => queueSelected(ownedPrompt.albums, 'skip')}
+                          className="flex-1 py-3 bg-white text-black rounded-xl font-bold text-xs hover:bg-white/90 transition-all"
+                        >
+                          Skip Owned
+                        </button>
+                        <button
+                          onClick={() => queueSelected(ownedPrompt.albums, 'replace')}
+                          className="flex-1 py-3 bg-white/10 text-white rounded-xl font-bold text-xs hover:bg-white/20 transition-all"
+                        >
+                          Replace
+                        </button>
+                        <button
+                          onClick={() => queueSelected(ownedPrompt.albums, 'keep_both')}
+                          className="flex-1 py-3 bg-white/10 text-white rounded-xl font-bold text-xs hover:bg-white/20 transition-all"
+                        >
+                          Keep Both
+                        </button>
+                        <button
+                          onClick={() => setOwnedPrompt(null)}
+                          className="px-4 py-3 text-white/40 hover:text-white text-xs font-medium transition-colors"
+                        >
+                          Cancel
+                        </button>
+                      </div>
+                    </div>
+                  )}
+
                   {/* Download button */}
-                  {selected.size > 0 && (
+                  {selected.size > 0 && !ownedPrompt && (
                     <div className="pt-4">
                       <button
                         onClick={handleDownloadSelected}
-                        className="w-full py-4 bg-white text-black rounded-xl font-bold text-sm hover:bg-white/90 transition-all"
+                        disabled={isCheckingOwned}
+                        className="w-full py-4 bg-white text-black rounded-xl font-bold text-sm hover:bg-white/90 transition-all disabled:opacity-50"
                       >
-                        {`Download ${selected.size} Album${selected.size > 1 ? 's' : ''}`}
+                        {isCheckingOwned
+                          ? 'Checking library...'
+                          : `Download ${selected.size} Album${selected.size > 1 ? 's' : ''}`}
                       </button>
                     </div>
                   )}
@@ -666,6 +927,49 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
                   Search for an artist to browse their discography
                 </div>
               )}
+
+              {/* Followed artists */}
+              {!selectedArtist && artistResults.length === 0 && watchlist.length > 0 && (
+                <div className="space-y-2">
+                  <div className="flex items-center justify-between">
+                    <h3 className="text-xs font-bold tracking-[0.2em] text-white/40 uppercase">
+                      Following
+                    </h3>
+                    <button
+                      onClick={handleCheckWatchlist}
+                      disabled={isCheckingWatchlist}
+                      className="text-xs text-white/40 hover:text-white transition-colors font-medium disabled:opacity-30"
+                    >
+                      {isCheckingWatchlist ? 'Checking...' : 'Check for new releases'}
+                    </button>
+                  </div>
+                  <div className="space-y-1">
+                    {watchlist.map(a => (
+                      <div
+                        key={a.id}
+                        className="flex items-center gap-3 p-3 rounded-xl bg-white/[0.02] border border-white/5"
+                      >
+                        <button
+                          onClick={() => handleSelectArtist({ id: a.id, name: a.name, disambiguation: '' })}
+                          className="flex-1 text-left text-sm font-semibold hover:text-white/70 transition-colors"
+                        >
+                          {a.name}
+                        </button>
+                        <span className="text-xs text-white/20">
+                          {a.last_checked ? `Checked ${new Date(a.last_checked * 1000).toLocaleDateString()}` : 'Not checked yet'}
+                        </span>
+                        <button
+                          onClick={() => toggleFollow({ id: a.id, name: a.name, disambiguation: '' })}
+                          disabled={isFollowing}
+                          className="text-xs text-white/20 hover:text-red-400 transition-colors"
+                        >
+                          Unfollow
+                        </button>
+                      </div>
+                    ))}
+                  </div>
+                </div>
+              )}
             </div>
           ) : activeTab === 'songs' ? (
             /* ── Song Search Tab ──────────────────────────────────── */
@@ -869,6 +1173,9 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
                 </div>
               )}
             </div>
+          ) : activeTab === 'import' ? (
+            /* ── Import Tab ────────────────────────────────────────── */
+            <ImportView onToast={onToast} />
           ) : (
             /* ── Manual Entry Tab ──────────────────────────────────── */
             <div className="space-y-4">
@@ -943,7 +1250,7 @@ export const DownloaderView: React.FC<PluginViewProps> = ({ onToast }) => {
 
         {/* ── Right column: Download Queue ──────────────────────── */}
         <div className="lg:w-80 flex-shrink-0">
-          <DownloadQueue onToast={onToast} onAllComplete={handleAllComplete} />
+          <DownloadQueue onToast={onToast} />
         </div>
       </div>
     </div>
@@ -0,0 +1,406 @@
+import React, { useState, useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { invoke } from '@tauri-apps/api/core';
+
+interface ImportFile {
+  path: string;
+  title: string;
+  artist: string;
+  track: number | null;
+  disc: number | null;
+  duration_secs: number | null;
+  has_cover: boolean;
+}
+
+interface ImportAlbum {
+  id: string;
+  dir: string;
+  artist: string;
+  album: string;
+  year: string;
+  genre: string;
+  files: ImportFile[];
+  cover_file: string | null;
+}
+
+interface AlbumTrack {
+  title: string;
+  disc: number;
+  track: number;
+  disc_tracks: number;
+  length_ms?: number | null;
+}
+
+interface ImportMatch {
+  release_id: string | null;
+  release_group_id: string | null;
+  artist: string;
+  album: string;
+  year: string;
+  genre: string;
+  tracks: AlbumTrack[];
+  assignments: (number | null)[];
+}
+
+interface MbRelease {
+  id: string;
+  title: string;
+  disambiguation: string;
+  status: string;
+  date: string;
+  country: string;
+  format: string;
+  disc_count: number;
+  track_count: number;
+}
+
+interface ImportProgress {
+  done: number;
+  total: number;
+  file: string;
+  written: string | null;
+}
+
+interface ImportReport {
+  imported: number;
+  skipped: string[];
+  failed: string[];
+}
+
+type ImportMode = 'copy' | 'move';
+
+interface ImportViewProps {
+  onToast: (msg: string) => void;
+}
+
+const fileName = (path: string) => path.split(/[\\/]/).pop() || path;
+
+const inputClass =
+  'bg-white/5 border border-white/5 rounded-lg px-3 py-2 text-sm text-white placeholder:text-white/20 focus:outline-none focus:border-white/20 transition-colors';
+
+export const ImportView: React.FC<ImportViewProps> = ({ onToast }) => {
+  const [sourcePath, setSourcePath] = useState('');
+  const [isScanning, setIsScanning] = useState(false);
+  const [albums, setAlbums] = useState<ImportAlbum[]>([]);
+  const [matches, setMatches] = useState<Record<string, ImportMatch>>({});
+  const [matching, setMatching] = useState<Set<string>>(new Set());
+  const [included, setIncluded] = useState<Set<string>>(new Set());
+  const [editions, setEditions] = useState<Record<string, MbRelease[]>>({});
+  const [expanded, setExpanded] = useState<string | null>(null);
+  const [mode, setMode] = useState<ImportMode>('copy');
+  const [progress, setProgress] = useState<ImportProgress | null>(null);
+
+  useEffect(() => {
+    const unlisteners: (() => void)[] = [];
+
+    listen<ImportProgress>('import-progress', (event) => {
+      setProgress(event.payload);
+    }).then(u => unlisteners.push(u));
+
+    listen<ImportReport>('import-complete', (event) => {
+      const { imported, skipped, failed } = event.payload;
+      setProgress(null);
+      setAlbums([]);
+      setMatches({});
+      onToast(
+        `Imported ${imported} file${imported !== 1 ? 's' : ''}` +
+          (skipped.length ? `, ${skipped.length} skipped` : '') +
+          (failed.length ? `, ${failed.length} failed` : '')
+      );
+    }).then(u => unlisteners.push(u));
+
+    return () => { unlisteners.forEach(u => u()); };
+  }, [onToast]);
+
+  const matchAlbum = async (album: ImportAlbum, releaseId: string | null = null) => {
+    setMatching(prev => new Set(prev).add(album.id));
+    try {
+      const matched = await invoke<ImportMatch>('downloader_import_match', { album, releaseId });
+      setMatches(prev => ({ ...prev, [album.id]: matched }));
+      if (matched.release_group_id && !editions[album.id]) {
+        invoke<MbRelease[]>('downloader_get_releases', { releaseGroupId: matched.release_group_id })
+          .then(releases => setEditions(prev => ({ ...prev, [album.id]: releases })))
+          .catch(() => {});
+      }
+    } catch (e: any) {
+      onToast(`Matching ${album.album} failed: ${e}`);
+    } finally {
+      setMatching(prev => {
+        const next = new Set(prev);
+        next.delete(album.id);
+        return next;
+      });
+    }
+  };
+
+  const handleScan = async () => {
+    if (!sourcePath.trim()) return;
+    setIsScanning(true);
+    setAlbums([]);
+    setMatches({});
+    setEditions({});
+    try {
+      const found = await invoke<ImportAlbum[]>('downloader_import_scan', { path: sourcePath });
+      setAlbums(found);
+      setIncluded(new Set(found.map(a => a.id)));
+      if (found.length === 0) onToast('No audio files found');
+      // One at a time: MusicBrainz is rate-limited anyway
+      for (const album of found) {
+        await matchAlbum(album);
+      }
+    } catch (e: any) {
+      onToast(`Scan failed: ${e}`);
+    } finally {
+      setIsScanning(false);
+    }
+  };
+
+  const updateMatch = (id: string, field: 'artist' | 'album' | 'year' | 'genre', value: string) => {
+    setMatches(prev => ({ ...prev, [id]: { ...prev[id], [field]: value } }));
+  };
+
+  const assignTrack = (id: string, fileIndex: number, trackIndex: number | null) => {
+    setMatches(prev => {
+      const assignments = [...prev[id].assignments];
+      assignments[fileIndex] = trackIndex;
+      return { ...prev, [id]: { ...prev[id], assignments } };
+    });
+  };
+
+  const toggleIncluded = (id: string) => {
+    setIncluded(prev => {
+      const next = new Set(prev);
+      if (next.has(id)) next.delete(id);
+      else next.add(id);
+      return next;
+    });
+  };
+
+  const ready = albums.filter(a => included.has(a.id) && matches[a.id]);
+  const fileCount = ready.reduce(
+    (sum, a) => sum + matches[a.id].assignments.filter(t => t !== null).length,
+    0
+  );
+
+  const handleImport = async () => {
+    if (ready.length === 0) return;
+    try {
+      await invoke('downloader_import_apply', {
+        albums: ready.map(album => ({ album, matched: matches[album.id] })),
+        mode,
+      });
+      setProgress({ done: 0, total: ready.reduce((sum, a) => sum + a.files.length, 0), file: '', written: null });
+    } catch (e: any) {
+      onToast(`Import failed: ${e}`);
+    }
+  };
+
+  const editionLabel = (r: MbRelease) =>
+    [r.date || 'Undated', r.country, r.format, `${r.track_count} tracks`, r.disambiguation]
+      .filter(Boolean)
+      .join(' · ');
+
+  const trackLabel = (t: AlbumTrack, multiDisc: boolean) =>
+    `${multiDisc ? `${t.disc}-` : ''}${String(t.track).padStart(2, '0')}. ${t.title}`;
+
+  return (
+    <div className="space-y-6">
+      {/* Source folder */}
+      <div className="flex gap-3">
+        <input
+          type="text"
+          placeholder="Folder or .zip to import..."
+          value={sourcePath}
+          onChange={e => setSourcePath(e.target.value)}
+          onKeyDown={e => e.key === 'Enter' && handleScan()}
+          className="flex-1 bg-white/5 border border-white/5 rounded-xl px-5 py-3 text-sm text-white placeholder:text-white/20 focus:outline-none focus:bg-white/10 focus:border-white/20 transition-colors"
+        />
+        <button
+          onClick={handleScan}
+          disabled={isScanning || progress !== null || !sourcePath.trim()}
+          className="px-6 py-3 bg-white text-black rounded-xl text-sm font-bold hover:bg-white/90 disabled:opacity-30 transition-all"
+        >
+          {isScanning ? 'Scanning...' : 'Scan'}
+        </button>
+      </div>
+
+      {/* Albums to review */}
+      {albums.map(album => {
+        const matched = matches[album.id];
+        const isOpen = expanded === album.id;
+        const multiDisc = matched ? matched.tracks.some(t => t.disc > 1) : false;
+        const unassigned = matched ? matched.assignments.filter(t => t === null).length : 0;
+        return (
+          <div
+            key={album.id}
+            className={`bg-white/[0.03] border rounded-2xl p-5 space-y-3 ${
+              included.has(album.id) ? 'border-white/10' : 'border-white/5 opacity-50'
+            }`}
+          >
+            <div className="flex items-start gap-3">
+              <input
+                type="checkbox"
+                checked={included.has(album.id)}
+                onChange={() => toggleIncluded(album.id)}
+                className="mt-1"
+              />
+              <div className="flex-1 min-w-0">
+                <div className="text-sm font-medium truncate">
+                  {album.artist || 'Unknown artist'} - {album.album || 'Unknown album'}
+                </div>
+                <div className="text-xs text-white/30 truncate">
+                  {album.files.length} file{album.files.length !== 1 ? 's' : ''} · {album.dir}
+                </div>
+              </div>
+              <span className="text-xs text-white/40 flex-shrink-0">
+                {matching.has(album.id)
+                  ? 'Matching...'
+                  : !matched
+                    ? 'Not matched'
+                    : matched.release_id
+                      ? unassigned > 0
+                        ? `${unassigned} unmatched`
+                        : 'Matched'
+                      : 'Not on MusicBrainz'}
+              </span>
+            </div>
+
+            {matched && (
+              <>
+                <div className="grid grid-cols-2 gap-2">
+                  <input
+                    type="text"
+                    placeholder="Artist"
+                    value={matched.artist}
+                    onChange={e => updateMatch(album.id, 'artist', e.target.value)}
+                    className={inputClass}
+                  />
+                  <input
+                    type="text"
+                    placeholder="Album"
+                    value={matched.album}
+                    onChange={e => updateMatch(album.id, 'album', e.target.value)}
+                    className={inputClass}
+                  />
+                  <input
+                    type="text"
+                    placeholder="Year"
+                    value={matched.year}
+                    onChange={e => updateMatch(album.id, 'year', e.target.value)}
+                    className={inputClass}
+                  />
+                  <input
+                    type="text"
+                    placeholder="Genre"
+                    value={matched.genre}
+                    onChange={e => updateMatch(album.id, 'genre', e.target.value)}
+                    className={inputClass}
+                  />
+                </div>
+
+                {editions[album.id] && editions[album.id].length > 1 && (
+                  <select
+                    value={matched.release_id || ''}
+                    onChange={e => matchAlbum(album, e.target.value)}
+                    disabled={matching.has(album.id)}
+                    className={`w-full ${inputClass}`}
+                  >
+                    {editions[album.id].map(r => (
+                      <option key={r.id} value={r.id}>
+                        {editionLabel(r)}
+                      </option>
+                    ))}
+                  </select>
+                )}
+
+                <button
+                  onClick={() => setExpanded(isOpen ? null : album.id)}
+                  className="text-xs text-white/30 hover:text-white/60 transition-colors"
+                >
+                  {isOpen ? 'Hide tracks' : 'Review tracks'}
+                </button>
+
+                {isOpen && (
+                  <div className="space-y-1">
+                    {album.files.map((file, i) => (
+                      <div key={file.path} className="flex items-center gap-2 text-xs">
+                        <span className="flex-1 min-w-0 truncate text-white/50" title={file.path}>
+                          {fileName(file.path)}
+                        </span>
+                        <select
+                          value={matched.assignments[i] ?? ''}
+                          onChange={e =>
+                            assignTrack(album.id, i, e.target.value === '' ? null : Number(e.target.value))
+                          }
+                          className="w-1/2 bg-white/5 border border-white/5 rounded-lg px-2 py-1 text-xs text-white focus:outline-none focus:border-white/20"
+                        >
+                          <option value="">Skip</option>
+                          {matched.tracks.map((t, ti) => (
+                            <option key={ti} value={ti}>
+                              {trackLabel(t, multiDisc)}
+                            </option>
+                          ))}
+                        </select>
+                      </div>
+                    ))}
+                  </div>
+                )}
+              </>
+            )}
+          </div>
+        );
+      })}
+
+      {/* Import */}
+      {albums.length > 0 && (
+        <div className="space-y-3 pt-2">
+          <div className="flex gap-1 bg-white/5 rounded-xl p-1 w-fit border border-white/5">
+            {(['copy', 'move'] as ImportMode[]).map(m => (
+              <button
+                key={m}
+                onClick={() => setMode(m)}
+                className={`px-4 py-1.5 rounded-lg text-xs font-medium transition-colors ${
+                  mode === m ? 'bg-white/10 text-white' : 'text-white/40 hover:text-white/70'
+                }`}
+              >
+                {m === 'copy' ? 'Copy files' : 'Move files'}
+              </button>
+            ))}
+          </div>
+
+          {progress ? (
+            <div className="space-y-1">
+              <div className="h-1.5 bg-white/5 rounded-full overflow-hidden">
+                <div
+                  className="h-full bg-white/60 transition-all"
+                  style={{ width: `${progress.total ? (progress.done / progress.total) * 100 : 0}%` }}
+                />
+              </div>
+              <div className="text-xs text-white/30 truncate">
+                {progress.done}/{progress.total} {progress.file && fileName(progress.file)}
+              </div>
+            </div>
+          ) : (
+            <button
+              onClick={handleImport}
+              disabled={isScanning || matching.size > 0 || fileCount === 0}
+              className="w-full py-4 bg-white text-black rounded-xl font-bold text-sm hover:bg-white/90 disabled:opacity-30 transition-all"
+            >
+              {`Import ${fileCount} File${fileCount !== 1 ? 's' : ''}`}
+            </button>
+          )}
+        </div>
+      )}
+
+      {/* Empty state */}
+      {!isScanning && albums.length === 0 && (
+        <div className="text-center py-16 text-white/20 text-sm space-y-2">
+          <div>Import music you already have into the library</div>
+          <div className="text-xs text-white/10">
+            Files are matched against MusicBrainz, retagged and renamed like downloads. Nothing is written until you import.
+          </div>
+        </div>
+      )}
+    </div>
+  );
+};
@@ -1,47 +1,58 @@
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
-use crate::audio::engine::AudioEngineHandle;
+use crate::audio::engine::{AudioCommand, AudioEngineHandle, CommandResult};
+use crate::audio::error::AudioError;
 use crate::audio::state::{AudioState, TrackInfo};
 
+/// Send an acknowledged command and wait for the audio thread's answer.
+///
+/// Loads can take up to a minute to acknowledge, so the wait happens on the
+/// blocking pool rather than on the async runtime's workers, which would
+/// otherwise hold up every other async command.
+async fn request(app: AppHandle, cmd: AudioCommand) -> CommandResult {
+    tauri::async_runtime::spawn_blocking(move || app.state::<AudioEngineHandle>().request(cmd))
+        .await
+        .map_err(|e| AudioError::EngineUnavailable(e.to_string()))?
+}
+
 #[tauri::command]
-pub fn audio_play_track(
+pub async fn audio_play_track(
     track: TrackInfo,
     source_url: String,
-    engine: State<'_, AudioEngineHandle>,
-) -> Result<(), String> {
-    engine.play_track(track, &source_url)
+    app: AppHandle,
+) -> CommandResult {
+    log::info!("Playing track: {} - {}", track.artist, track.title);
+    request(app, AudioCommand::PlayTrack { track, source_url }).await
 }
 
 #[tauri::command]
-pub fn audio_pause(engine: State<'_, AudioEngineHandle>) {
-    engine.pause();
+pub async fn audio_pause(app: AppHandle) -> CommandResult {
+    request(app, AudioCommand::Pause).await
 }
 
 #[tauri::command]
-pub fn audio_resume(engine: State<'_, AudioEngineHandle>) {
-    engine.resume();
+pub async fn audio_resume(app: AppHandle) -> CommandResult {
+    request(app, AudioCommand::Resume).await
 }
 
 #[tauri::command]
-pub fn audio_toggle_play(engine: State<'_, AudioEngineHandle>) {
-    engine.toggle_play();
+pub async fn audio_toggle_play(app: AppHandle) -> CommandResult {
+    request(app, AudioCommand::TogglePlay).await
 }
 
 #[tauri::command]
-pub fn audio_stop(engine: State<'_, AudioEngineHandle>) {
-    engine.stop();
+pub async fn audio_stop(app: AppHandle) -> CommandResult {
+    request(app, AudioCommand::Stop).await
 }
 
 #[tauri::command]
-pub fn audio_seek(time_secs: f64, engine: State<'_, AudioEngineHandle>) {
-    engine.seek(time_secs);
+pub async fn audio_seek(time_secs: f64, app: AppHandle) -> CommandResult {
+    request(app, AudioCommand::Seek(time_secs)).await
 }
 
 #[tauri::command]
-pub fn audio_seek_percent(percent: f64, engine: State<'_, AudioEngineHandle>) {
-    let state = engine.get_state();
-    let position = state.duration_secs * (percent / 100.0);
-    engine.seek(position);
+pub async fn audio_seek_percent(percent: f64, app: AppHandle) -> CommandResult {
+    request(app, AudioCommand::SeekPercent(percent)).await
 }
 
 #[tauri::command]
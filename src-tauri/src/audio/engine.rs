@@ -3,23 +3,37 @@
 //! Architecture:
 //! - `AudioEngineHandle`: Tauri-managed handle (Send + Sync) that sends commands to audio thread
 //! - `AudioThread`: Dedicated thread that owns the audio output and processes commands
+//! - `Loader`: Worker thread that downloads and decodes tracks, so the audio thread
+//!   stays responsive while a track loads; newer loads supersede older ones
 //! - Uses crossbeam channels for thread-safe command passing
 //! - SharedState (Arc<RwLock<AudioState>>) for reading state from any thread
 
-use std::io::{BufReader, Cursor};
 use std::thread;
 use std::time::{Duration, Instant};
 
-use crossbeam_channel::{bounded, Receiver, Sender};
-use rodio::{Decoder, OutputStream, Sink};
+use crossbeam_channel::{
+    bounded, select, unbounded, Receiver, RecvTimeoutError, SendTimeoutError, Sender,
+};
+use rodio::{OutputStream, Sink};
 
-use crate::audio::events;
+use crate::audio::error::AudioError;
+use crate::audio::events::{self, EngineEvent, EventBus};
+use crate::audio::loader::{LoadResult, Loader};
 use crate::audio::source::TrackSource;
-use crate::audio::state::{create_shared_state, AudioState, SharedState, TrackInfo};
+use crate::audio::state::{create_shared_state, AudioState, RepeatMode, SharedState, TrackInfo};
 
 /// Interval for position updates and track-end checks
 const TICK_INTERVAL: Duration = Duration::from_millis(250);
 
+/// How long to wait for the audio thread to acknowledge a command
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long to wait for `PlayTrack`, which is acknowledged once the track has loaded
+const LOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Outcome of an acknowledged command: the engine state right after it ran.
+pub type CommandResult = Result<AudioState, AudioError>;
+
 /// Commands sent to the audio thread
 #[derive(Debug)]
 pub enum AudioCommand {
@@ -29,12 +43,41 @@ pub enum AudioCommand {
     },
     Pause,
     Resume,
+    TogglePlay,
     Stop,
     Seek(f64),
+    /// Seek to a percentage (0-100) of the current track's duration
+    SeekPercent(f64),
     SetVolume(f32),
     SetMuted(bool),
     ToggleShuffle,
+    SetShuffle(bool),
     CycleRepeat,
+    SetRepeat(RepeatMode),
+}
+
+impl AudioCommand {
+    /// Short name used in timeout errors.
+    fn name(&self) -> &'static str {
+        match self {
+            AudioCommand::PlayTrack { .. } => "play_track",
+            AudioCommand::Pause => "pause",
+            AudioCommand::Resume => "resume",
+            AudioCommand::TogglePlay => "toggle_play",
+            AudioCommand::Stop => "stop",
+            AudioCommand::Seek(_) | AudioCommand::SeekPercent(_) => "seek",
+            AudioCommand::SetVolume(_) => "set_volume",
+            AudioCommand::SetMuted(_) => "set_muted",
+            AudioCommand::ToggleShuffle | AudioCommand::SetShuffle(_) => "set_shuffle",
+            AudioCommand::CycleRepeat | AudioCommand::SetRepeat(_) => "set_repeat",
+        }
+    }
+}
+
+/// A command plus an optional channel for its acknowledgement.
+struct Envelope {
+    cmd: AudioCommand,
+    reply: Option<Sender<CommandResult>>,
 }
 
 /// Handle for accessing the audio engine from Tauri commands.
@@ -42,84 +85,146 @@ pub enum AudioCommand {
 /// This struct is Send + Sync and safe to manage with Tauri's state system.
 /// It communicates with the audio thread via a command channel.
 pub struct AudioEngineHandle {
-    cmd_tx: Sender<AudioCommand>,
+    cmd_tx: Sender<Envelope>,
     state: SharedState,
+    events: EventBus,
 }
 
 impl AudioEngineHandle {
     /// Create a new audio engine and spawn the audio thread.
     pub fn new(app_handle: tauri::AppHandle) -> Result<Self, String> {
-        let (cmd_tx, cmd_rx) = bounded::<AudioCommand>(32);
+        let (cmd_tx, cmd_rx) = bounded::<Envelope>(32);
         let state = create_shared_state();
+        let events = EventBus::default();
 
         // Spawn the audio thread
         let state_clone = state.clone();
+        let events_clone = events.clone();
         thread::Builder::new()
             .name("lumina-audio".into())
             .spawn(move || {
-                AudioThread::run(cmd_rx, state_clone, app_handle);
+                AudioThread::run(cmd_rx, state_clone, events_clone, app_handle);
             })
             .map_err(|e| format!("Failed to spawn audio thread: {}", e))?;
 
         log::info!("Audio engine initialized");
-        Ok(Self { cmd_tx, state })
+        Ok(Self {
+            cmd_tx,
+            state,
+            events,
+        })
+    }
+
+    /// Send a command without waiting for it to run.
+    fn send(&self, cmd: AudioCommand) {
+        let _ = self.cmd_tx.send(Envelope { cmd, reply: None });
+    }
+
+    /// Send a command and wait for the audio thread to report its outcome.
+    ///
+    /// Returns the engine state right after the command ran. A thread that
+    /// does not answer in time is reported as `AudioError::Timeout`.
+    pub fn request(&self, cmd: AudioCommand) -> CommandResult {
+        let name = cmd.name();
+        let timeout = match cmd {
+            AudioCommand::PlayTrack { .. } => LOAD_TIMEOUT,
+            _ => COMMAND_TIMEOUT,
+        };
+
+        let (reply_tx, reply_rx) = bounded(1);
+        let envelope = Envelope {
+            cmd,
+            reply: Some(reply_tx),
+        };
+        self.cmd_tx
+            .send_timeout(envelope, COMMAND_TIMEOUT)
+            .map_err(|e| match e {
+                SendTimeoutError::Timeout(_) => AudioError::Timeout(name.to_string()),
+                SendTimeoutError::Disconnected(_) => {
+                    AudioError::EngineUnavailable("audio thread has stopped".into())
+                }
+            })?;
+
+        match reply_rx.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(AudioError::Timeout(name.to_string())),
+            Err(RecvTimeoutError::Disconnected) => Err(AudioError::EngineUnavailable(
+                "audio thread dropped the request".into(),
+            )),
+        }
     }
 
     /// Play a track from the given source URL.
-    pub fn play_track(&self, track: TrackInfo, source_url: &str) -> Result<(), String> {
+    ///
+    /// Returns once the command is queued; use `request` to wait for the load.
+    pub fn play_track(&self, track: TrackInfo, source_url: &str) -> Result<(), AudioError> {
         log::info!("Playing track: {} - {}", track.artist, track.title);
         self.cmd_tx
-            .send(AudioCommand::PlayTrack {
-                track,
-                source_url: source_url.to_string(),
+            .send(Envelope {
+                cmd: AudioCommand::PlayTrack {
+                    track,
+                    source_url: source_url.to_string(),
+                },
+                reply: None,
             })
-            .map_err(|e| format!("Audio thread not responding: {}", e))
+            .map_err(|e| AudioError::EngineUnavailable(e.to_string()))
     }
 
     pub fn pause(&self) {
-        let _ = self.cmd_tx.send(AudioCommand::Pause);
+        self.send(AudioCommand::Pause);
     }
 
     pub fn resume(&self) {
-        let _ = self.cmd_tx.send(AudioCommand::Resume);
+        self.send(AudioCommand::Resume);
     }
 
     pub fn toggle_play(&self) {
-        if self.state.read().is_playing {
-            self.pause();
-        } else {
-            self.resume();
-        }
+        self.send(AudioCommand::TogglePlay);
     }
 
     pub fn stop(&self) {
-        let _ = self.cmd_tx.send(AudioCommand::Stop);
+        self.send(AudioCommand::Stop);
     }
 
     pub fn seek(&self, position_secs: f64) {
-        let _ = self.cmd_tx.send(AudioCommand::Seek(position_secs));
+        self.send(AudioCommand::Seek(position_secs));
     }
 
     pub fn set_volume(&self, volume: f32) {
-        let _ = self.cmd_tx.send(AudioCommand::SetVolume(volume));
+        self.send(AudioCommand::SetVolume(volume));
     }
 
     pub fn toggle_mute(&self) {
         let is_muted = self.state.read().is_muted;
-        let _ = self.cmd_tx.send(AudioCommand::SetMuted(!is_muted));
+        self.send(AudioCommand::SetMuted(!is_muted));
     }
 
     pub fn toggle_shuffle(&self) {
-        let _ = self.cmd_tx.send(AudioCommand::ToggleShuffle);
+        self.send(AudioCommand::ToggleShuffle);
+    }
+
+    pub fn set_shuffle(&self, shuffled: bool) {
+        self.send(AudioCommand::SetShuffle(shuffled));
     }
 
     pub fn cycle_repeat(&self) {
-        let _ = self.cmd_tx.send(AudioCommand::CycleRepeat);
+        self.send(AudioCommand::CycleRepeat);
+    }
+
+    pub fn set_repeat(&self, mode: RepeatMode) {
+        self.send(AudioCommand::SetRepeat(mode));
     }
 
     pub fn get_state(&self) -> AudioState {
         self.state.read().clone()
     }
+
+    /// Subscribe to engine events from within the backend.
+    ///
+    /// The frontend receives the same events through Tauri's event system.
+    pub fn subscribe(&self) -> Receiver<EngineEvent> {
+        self.events.subscribe()
+    }
 }
 
 /// Tracks playback position using wall-clock time.
@@ -185,6 +290,27 @@ impl PositionTracker {
     }
 }
 
+/// A track whose source is still being loaded.
+struct PendingLoad {
+    generation: u64,
+    track: TrackInfo,
+    /// Start playing once loaded (cleared by Pause while loading)
+    autoplay: bool,
+    /// Seek requested while loading, applied once the source is in the sink
+    start_at: Option<f64>,
+    /// Acknowledged once the load finishes or is superseded
+    reply: Option<Sender<CommandResult>>,
+}
+
+impl PendingLoad {
+    /// Tell a waiting caller that this load will never start.
+    fn cancel(self) {
+        if let Some(reply) = self.reply {
+            let _ = reply.send(Err(AudioError::Cancelled));
+        }
+    }
+}
+
 /// The audio processing thread.
 ///
 /// This thread owns the Rodio OutputStream and Sink, which are not Send.
@@ -193,8 +319,12 @@ struct AudioThread {
     _stream: OutputStream,
     sink: Sink,
     state: SharedState,
+    events: EventBus,
     app_handle: tauri::AppHandle,
     position: PositionTracker,
+    loader: Loader,
+    /// Load in flight, if any (only the latest request is kept)
+    pending: Option<PendingLoad>,
     /// Track ID of currently playing track (for track-end events)
     current_track_id: Option<String>,
     /// Last time we emitted a state update
@@ -203,14 +333,19 @@ struct AudioThread {
 
 impl AudioThread {
     /// Main loop for the audio thread.
-    fn run(cmd_rx: Receiver<AudioCommand>, state: SharedState, app_handle: tauri::AppHandle) {
+    fn run(
+        cmd_rx: Receiver<Envelope>,
+        state: SharedState,
+        events: EventBus,
+        app_handle: tauri::AppHandle,
+    ) {
         // Initialize audio output on this thread
         let (stream, stream_handle) = match OutputStream::try_default() {
             Ok(s) => s,
             Err(e) => {
                 log::error!("Failed to open audio output: {}", e);
                 let mut state = state.write();
-                state.error = Some(format!("Audio output unavailable: {}", e));
+                state.error = Some(AudioError::Device(e.to_string()));
                 return;
             }
         };
@@ -220,7 +355,16 @@ impl AudioThread {
             Err(e) => {
                 log::error!("Failed to create audio sink: {}", e);
                 let mut state = state.write();
-                state.error = Some(format!("Failed to initialize audio: {}", e));
+                state.error = Some(AudioError::Device(e.to_string()));
+                return;
+            }
+        };
+
+        let (load_tx, load_rx) = unbounded::<LoadResult>();
+        let loader = match Loader::spawn(load_tx) {
+            Ok(l) => l,
+            Err(e) => {
+                log::error!("{}", e);
                 return;
             }
         };
@@ -231,24 +375,32 @@ impl AudioThread {
             _stream: stream,
             sink,
             state,
+            events,
             app_handle,
             position: PositionTracker::new(),
+            loader,
+            pending: None,
             current_track_id: None,
             last_state_emit: Instant::now(),
         };
 
-        // Main loop: process commands with timeout for periodic tasks
+        // Main loop: process commands and finished loads, with timeout for periodic tasks
         loop {
-            match cmd_rx.recv_timeout(TICK_INTERVAL) {
-                Ok(cmd) => thread.handle_command(cmd),
-                Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
-                    // Periodic tick - update position and check track end
-                    thread.tick();
-                }
-                Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
-                    log::info!("Audio thread shutting down");
-                    break;
-                }
+            select! {
+                recv(cmd_rx) -> envelope => match envelope {
+                    Ok(Envelope { cmd, reply }) => thread.handle_command(cmd, reply),
+                    Err(_) => {
+                        log::info!("Audio thread shutting down");
+                        break;
+                    }
+                },
+                recv(load_rx) -> loaded => {
+                    if let Ok(loaded) = loaded {
+                        thread.on_loaded(loaded);
+                    }
+                },
+                // Periodic tick - update position and check track end
+                default(TICK_INTERVAL) => thread.tick(),
             }
         }
     }
@@ -262,7 +414,8 @@ impl AudioThread {
         }
 
         // Update position in state and emit events (~4Hz when playing)
-        if self.position.is_playing() && self.last_state_emit.elapsed() >= Duration::from_millis(250)
+        if self.position.is_playing()
+            && self.last_state_emit.elapsed() >= Duration::from_millis(250)
         {
             let position = self.position.position();
             {
@@ -280,7 +433,7 @@ impl AudioThread {
 
         // Emit track ended event
         if let Some(track_id) = self.current_track_id.take() {
-            events::emit_track_ended(&self.app_handle, &track_id);
+            events::emit_track_ended(&self.app_handle, &self.events, &track_id);
         }
 
         // Reset state
@@ -294,48 +447,138 @@ impl AudioThread {
         self.emit_state();
     }
 
-    fn handle_command(&mut self, cmd: AudioCommand) {
-        match cmd {
+    fn handle_command(&mut self, cmd: AudioCommand, reply: Option<Sender<CommandResult>>) {
+        let result = match cmd {
             AudioCommand::PlayTrack { track, source_url } => {
-                self.play_track(track, &source_url);
+                // Acknowledged from `on_loaded` once the track has loaded
+                self.play_track(track, &source_url, reply);
+                return;
             }
             AudioCommand::Pause => self.pause(),
             AudioCommand::Resume => self.resume(),
-            AudioCommand::Stop => self.stop(),
+            AudioCommand::TogglePlay => {
+                // While loading, toggle whether the track starts once loaded
+                let playing = match &self.pending {
+                    Some(pending) => pending.autoplay,
+                    None => self.state.read().is_playing,
+                };
+                if playing {
+                    self.pause()
+                } else {
+                    self.resume()
+                }
+            }
+            AudioCommand::Stop => {
+                self.stop();
+                Ok(())
+            }
             AudioCommand::Seek(pos) => self.seek(pos),
-            AudioCommand::SetVolume(vol) => self.set_volume(vol),
-            AudioCommand::SetMuted(muted) => self.set_muted(muted),
-            AudioCommand::ToggleShuffle => self.toggle_shuffle(),
-            AudioCommand::CycleRepeat => self.cycle_repeat(),
+            AudioCommand::SeekPercent(percent) => {
+                let duration = self.state.read().duration_secs;
+                self.seek(duration * (percent / 100.0))
+            }
+            AudioCommand::SetVolume(vol) => {
+                self.set_volume(vol);
+                Ok(())
+            }
+            AudioCommand::SetMuted(muted) => {
+                self.set_muted(muted);
+                Ok(())
+            }
+            AudioCommand::ToggleShuffle => {
+                self.toggle_shuffle();
+                Ok(())
+            }
+            AudioCommand::SetShuffle(shuffled) => {
+                self.set_shuffle(shuffled);
+                Ok(())
+            }
+            AudioCommand::CycleRepeat => {
+                self.cycle_repeat();
+                Ok(())
+            }
+            AudioCommand::SetRepeat(mode) => {
+                self.set_repeat(mode);
+                Ok(())
+            }
+        };
+
+        if let Some(reply) = reply {
+            let _ = reply.send(result.map(|()| self.state.read().clone()));
         }
     }
 
-    fn play_track(&mut self, track: TrackInfo, source_url: &str) {
+    fn play_track(
+        &mut self,
+        track: TrackInfo,
+        source_url: &str,
+        reply: Option<Sender<CommandResult>>,
+    ) {
         let source = TrackSource::from_url(source_url);
 
+        // Stop the previous track now so it can't end (and advance the queue) mid-load
+        self.sink.stop();
+        self.position.reset();
+        self.current_track_id = None;
+
         // Update state to loading
         {
             let mut state = self.state.write();
             state.is_loading = true;
+            state.is_playing = false;
+            state.position_secs = 0.0;
             state.error = None;
             state.current_track = Some(track.clone());
+            state.current_source = Some(source.clone());
             state.duration_secs = track.duration_secs;
         }
         self.emit_state();
 
-        // Store track ID for end detection
-        self.current_track_id = Some(track.id.clone());
-
-        // Load and play
-        let result = match source {
-            TrackSource::LocalFile { path } => self.load_local_file(&path),
-            TrackSource::HttpStream { url } => self.load_http_stream(&url),
+        // Supersedes any load still in flight
+        if let Some(previous) = self.pending.take() {
+            previous.cancel();
+        }
+        let generation = self.loader.load(source);
+        self.pending = Some(PendingLoad {
+            generation,
+            track,
+            autoplay: true,
+            start_at: None,
+            reply,
+        });
+    }
+
+    /// Start playback of a finished load, unless a newer request superseded it.
+    fn on_loaded(&mut self, loaded: LoadResult) {
+        let pending = match self.pending.take() {
+            Some(p) if p.generation == loaded.generation => p,
+            other => {
+                self.pending = other;
+                return;
+            }
         };
+        let track = pending.track;
 
-        match result {
-            Ok(()) => {
+        let outcome = match loaded.result {
+            Ok(source) => {
+                self.sink.stop();
+                self.sink.append(source);
+                self.current_track_id = Some(track.id.clone());
                 self.position.reset();
-                self.position.start();
+
+                if let Some(start_at) = pending.start_at {
+                    match self.sink.try_seek(Duration::from_secs_f64(start_at)) {
+                        Ok(()) => self.position.seek(start_at),
+                        Err(e) => log::warn!("Seek after load failed: {}", e),
+                    }
+                }
+
+                if pending.autoplay {
+                    self.sink.play();
+                    self.position.start();
+                } else {
+                    self.sink.pause();
+                }
 
                 // Apply current volume
                 let volume = {
@@ -351,70 +594,40 @@ impl AudioThread {
                 {
                     let mut state = self.state.write();
                     state.is_loading = false;
-                    state.is_playing = true;
-                    state.position_secs = 0.0;
+                    state.is_playing = pending.autoplay;
+                    state.position_secs = self.position.position();
                 }
                 self.emit_state();
-                events::emit_track_changed(&self.app_handle, &track);
+                events::emit_track_changed(&self.app_handle, &self.events, &track);
                 log::debug!("Playback started");
+                Ok(self.state.read().clone())
             }
             Err(e) => {
                 log::error!("Failed to play track: {}", e);
-                self.current_track_id = None;
                 {
                     let mut state = self.state.write();
                     state.is_loading = false;
                     state.is_playing = false;
-                    state.error = Some(e);
+                    state.error = Some(e.clone());
                 }
                 self.emit_state();
+                Err(e)
             }
-        }
-    }
-
-    fn load_local_file(&mut self, path: &std::path::Path) -> Result<(), String> {
-        log::debug!("Loading local file: {}", path.display());
-
-        let file = std::fs::File::open(path)
-            .map_err(|e| format!("Cannot open file: {}", e))?;
-
-        let decoder = Decoder::new(BufReader::new(file))
-            .map_err(|e| format!("Unsupported audio format: {}", e))?;
+        };
 
-        self.sink.stop();
-        self.sink.append(decoder);
-        self.sink.play();
-        Ok(())
+        if let Some(reply) = pending.reply {
+            let _ = reply.send(outcome);
+        }
     }
 
-    fn load_http_stream(&mut self, url: &str) -> Result<(), String> {
-        log::debug!("Loading HTTP stream: {}", url);
-
-        let response = reqwest::blocking::get(url)
-            .map_err(|e| format!("Network error: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!("Server error: {}", response.status()));
+    fn pause(&mut self) -> Result<(), AudioError> {
+        if let Some(pending) = self.pending.as_mut() {
+            pending.autoplay = false;
+            return Ok(());
         }
 
-        let bytes = response
-            .bytes()
-            .map_err(|e| format!("Failed to download: {}", e))?;
-
-        log::debug!("Downloaded {} bytes", bytes.len());
-
-        let decoder = Decoder::new(Cursor::new(bytes.to_vec()))
-            .map_err(|e| format!("Unsupported audio format: {}", e))?;
-
-        self.sink.stop();
-        self.sink.append(decoder);
-        self.sink.play();
-        Ok(())
-    }
-
-    fn pause(&mut self) {
         if !self.state.read().is_playing {
-            return; // Already paused
+            return Ok(()); // Already paused
         }
 
         self.sink.pause();
@@ -427,16 +640,22 @@ impl AudioThread {
         }
         self.emit_state();
         log::debug!("Paused at {:.1}s", self.position.position());
+        Ok(())
     }
 
-    fn resume(&mut self) {
+    fn resume(&mut self) -> Result<(), AudioError> {
+        if let Some(pending) = self.pending.as_mut() {
+            pending.autoplay = true;
+            return Ok(());
+        }
+
         // Can't resume if nothing is loaded
         if self.sink.empty() {
-            return;
+            return Err(AudioError::NothingLoaded);
         }
 
         if self.state.read().is_playing {
-            return; // Already playing
+            return Ok(()); // Already playing
         }
 
         self.sink.play();
@@ -448,43 +667,66 @@ impl AudioThread {
         }
         self.emit_state();
         log::debug!("Resumed");
+        Ok(())
     }
 
     fn stop(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            self.loader.cancel();
+            pending.cancel();
+        }
         self.sink.stop();
         self.position.reset();
         self.current_track_id = None;
 
         {
             let mut state = self.state.write();
+            state.is_loading = false;
             state.is_playing = false;
             state.position_secs = 0.0;
             state.current_track = None;
+            state.current_source = None;
         }
         self.emit_state();
         log::debug!("Stopped");
     }
 
-    fn seek(&mut self, position_secs: f64) {
+    fn seek(&mut self, position_secs: f64) -> Result<(), AudioError> {
+        // NaN would get through `clamp` and panic in `Duration::from_secs_f64`
+        if !position_secs.is_finite() {
+            return Err(AudioError::Seek(format!("Invalid position: {position_secs}")));
+        }
         let duration = self.state.read().duration_secs;
         let clamped = position_secs.clamp(0.0, duration);
 
-        match self.sink.try_seek(Duration::from_secs_f64(clamped)) {
+        if let Some(pending) = self.pending.as_mut() {
+            pending.start_at = Some(clamped);
+            return Ok(());
+        }
+
+        if self.sink.empty() {
+            return Err(AudioError::NothingLoaded);
+        }
+
+        let result = match self.sink.try_seek(Duration::from_secs_f64(clamped)) {
             Ok(()) => {
                 log::debug!("Seeked to {:.1}s", clamped);
+                self.position.seek(clamped);
+                let mut state = self.state.write();
+                state.position_secs = clamped;
+                Ok(())
             }
             Err(e) => {
+                // Playback continues from where it was; keep the position as-is
                 log::warn!("Seek failed: {}", e);
+                let error = AudioError::Seek(e.to_string());
+                let mut state = self.state.write();
+                state.error = Some(error.clone());
+                Err(error)
             }
-        }
-
-        self.position.seek(clamped);
-
-        {
-            let mut state = self.state.write();
-            state.position_secs = clamped;
-        }
+        };
         self.emit_state();
+        result
     }
 
     fn set_volume(&mut self, volume: f32) {
@@ -517,6 +759,14 @@ impl AudioThread {
         self.emit_state();
     }
 
+    fn set_shuffle(&mut self, shuffled: bool) {
+        {
+            let mut state = self.state.write();
+            state.is_shuffled = shuffled;
+        }
+        self.emit_state();
+    }
+
     fn cycle_repeat(&mut self) {
         {
             let mut state = self.state.write();
@@ -525,9 +775,17 @@ impl AudioThread {
         self.emit_state();
     }
 
+    fn set_repeat(&mut self, mode: RepeatMode) {
+        {
+            let mut state = self.state.write();
+            state.repeat_mode = mode;
+        }
+        self.emit_state();
+    }
+
     /// Emit current state to frontend
     fn emit_state(&self) {
         let state = self.state.read();
-        events::emit_state_update(&self.app_handle, &state);
+        events::emit_state_update(&self.app_handle, &self.events, &state);
     }
 }
@@ -0,0 +1,107 @@
+//! Typed errors for the audio engine.
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+/// Errors surfaced by the audio engine.
+///
+/// Serialized as `{ kind, message, ... }` so the frontend can offer an
+/// action per `kind` (retry, skip, pick a device) instead of showing a string.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum AudioError {
+    /// The request never produced a response (DNS, connection, timeout, ...)
+    #[error("Network error: {0}")]
+    Network(String),
+    /// The server responded with a non-success status
+    #[error("Server error: HTTP {status}")]
+    HttpStatus { status: u16 },
+    /// The stream was recognized but could not be decoded
+    #[error("Failed to decode audio: {0}")]
+    Decode(String),
+    /// No decoder recognized the stream
+    #[error("Unsupported audio format: {0}")]
+    UnsupportedFormat(String),
+    #[error("File not found: {path}")]
+    FileNotFound { path: String },
+    /// The file exists but could not be opened (permissions, ...)
+    #[error("Cannot open file {path}: {message}")]
+    FileUnreadable { path: String, message: String },
+    /// The audio output device could not be opened or used
+    #[error("Audio output unavailable: {0}")]
+    Device(String),
+    #[error("Seek failed: {0}")]
+    Seek(String),
+    /// The command needs a track but none is loaded
+    #[error("No track loaded")]
+    NothingLoaded,
+    /// A newer play or stop request replaced this one before it finished
+    #[error("Superseded by a newer request")]
+    Cancelled,
+    /// The audio thread did not acknowledge the command in time
+    #[error("Audio engine did not respond to {0} in time")]
+    Timeout(String),
+    /// The audio thread is gone or not accepting commands
+    #[error("Audio engine not responding: {0}")]
+    EngineUnavailable(String),
+}
+
+impl AudioError {
+    /// Machine-readable identifier for the error variant.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AudioError::Network(_) => "network",
+            AudioError::HttpStatus { .. } => "http_status",
+            AudioError::Decode(_) => "decode",
+            AudioError::UnsupportedFormat(_) => "unsupported_format",
+            AudioError::FileNotFound { .. } => "file_not_found",
+            AudioError::FileUnreadable { .. } => "file_unreadable",
+            AudioError::Device(_) => "device",
+            AudioError::Seek(_) => "seek",
+            AudioError::NothingLoaded => "nothing_loaded",
+            AudioError::Cancelled => "cancelled",
+            AudioError::Timeout(_) => "timeout",
+            AudioError::EngineUnavailable(_) => "engine_unavailable",
+        }
+    }
+
+    /// Map a file open failure for `path`.
+    pub fn from_file_error(path: &std::path::Path, err: std::io::Error) -> Self {
+        let path = path.display().to_string();
+        if err.kind() == std::io::ErrorKind::NotFound {
+            AudioError::FileNotFound { path }
+        } else {
+            AudioError::FileUnreadable {
+                path,
+                message: err.to_string(),
+            }
+        }
+    }
+}
+
+impl From<rodio::decoder::DecoderError> for AudioError {
+    fn from(err: rodio::decoder::DecoderError) -> Self {
+        use rodio::decoder::DecoderError;
+        match err {
+            DecoderError::UnrecognizedFormat | DecoderError::NoStreams => {
+                AudioError::UnsupportedFormat(err.to_string())
+            }
+            _ => AudioError::Decode(err.to_string()),
+        }
+    }
+}
+
+impl Serialize for AudioError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("AudioError", 3)?;
+        s.serialize_field("kind", self.kind())?;
+        s.serialize_field("message", &self.to_string())?;
+        match self {
+            AudioError::HttpStatus { status } => s.serialize_field("status", status)?,
+            AudioError::FileNotFound { path } | AudioError::FileUnreadable { path, .. } => {
+                s.serialize_field("path", path)?
+            }
+            _ => {}
+        }
+        s.end()
+    }
+}
@@ -1,7 +1,12 @@
+use std::sync::Arc;
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use parking_lot::Mutex;
 use serde::Serialize;
 use tauri::Emitter;
 
-use crate::audio::state::{AudioState, TrackInfo};
+use crate::audio::error::AudioError;
+use crate::audio::state::{AudioState, RepeatMode, TrackInfo};
 
 #[derive(Clone, Serialize)]
 pub struct AudioStateEvent {
@@ -12,7 +17,9 @@ pub struct AudioStateEvent {
     pub volume: f32,
     pub is_muted: bool,
     pub is_loading: bool,
-    pub error: Option<String>,
+    pub error: Option<AudioError>,
+    pub repeat_mode: RepeatMode,
+    pub is_shuffled: bool,
 }
 
 impl From<&AudioState> for AudioStateEvent {
@@ -26,6 +33,8 @@ impl From<&AudioState> for AudioStateEvent {
             is_muted: state.is_muted,
             is_loading: state.is_loading,
             error: state.error.clone(),
+            repeat_mode: state.repeat_mode,
+            is_shuffled: state.is_shuffled,
         }
     }
 }
@@ -40,25 +49,61 @@ pub struct TrackEndedEvent {
     pub track_id: String,
 }
 
-pub fn emit_state_update(app: &tauri::AppHandle, state: &AudioState) {
+/// Engine events delivered to in-process listeners (e.g. the MPD server).
+///
+/// Each variant carries the same payload as the matching frontend event and
+/// serializes as `{ "event": "<tauri event name>", "payload": ... }`.
+#[derive(Clone, Serialize)]
+#[serde(tag = "event", content = "payload")]
+pub enum EngineEvent {
+    #[serde(rename = "audio:state")]
+    State(AudioStateEvent),
+    #[serde(rename = "audio:track-changed")]
+    TrackChanged(TrackChangedEvent),
+    #[serde(rename = "audio:track-ended")]
+    TrackEnded(TrackEndedEvent),
+}
+
+/// Fan-out of engine events to in-process subscribers.
+///
+/// Subscribers whose receiver has been dropped are pruned on the next publish.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    subscribers: Arc<Mutex<Vec<Sender<EngineEvent>>>>,
+}
+
+impl EventBus {
+    pub fn subscribe(&self) -> Receiver<EngineEvent> {
+        let (tx, rx) = unbounded();
+        self.subscribers.lock().push(tx);
+        rx
+    }
+
+    fn publish(&self, event: EngineEvent) {
+        self.subscribers
+            .lock()
+            .retain(|tx| tx.send(event.clone()).is_ok());
+    }
+}
+
+pub fn emit_state_update(app: &tauri::AppHandle, bus: &EventBus, state: &AudioState) {
     let event: AudioStateEvent = state.into();
+    bus.publish(EngineEvent::State(event.clone()));
     let _ = app.emit("audio:state", event);
 }
 
-pub fn emit_track_changed(app: &tauri::AppHandle, track: &TrackInfo) {
-    let _ = app.emit(
-        "audio:track-changed",
-        TrackChangedEvent {
-            track: track.clone(),
-        },
-    );
+pub fn emit_track_changed(app: &tauri::AppHandle, bus: &EventBus, track: &TrackInfo) {
+    let event = TrackChangedEvent {
+        track: track.clone(),
+    };
+    bus.publish(EngineEvent::TrackChanged(event.clone()));
+    let _ = app.emit("audio:track-changed", event);
 }
 
-pub fn emit_track_ended(app: &tauri::AppHandle, track_id: &str) {
-    let _ = app.emit(
-        "audio:track-ended",
-        TrackEndedEvent {
-            track_id: track_id.to_string(),
-        },
-    );
+pub fn emit_track_ended(app: &tauri::AppHandle, bus: &EventBus, track_id: &str) {
+    let event = TrackEndedEvent {
+        track_id: track_id.to_string(),
+    };
+    bus.publish(EngineEvent::TrackEnded(event.clone()));
+    let _ = app.emit("audio:track-ended", event);
 }
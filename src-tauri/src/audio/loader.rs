@@ -0,0 +1,163 @@
+//! Background track loading.
+//!
+//! Downloading a stream and probing its format can take seconds, so it runs on
+//! a dedicated loader thread instead of the audio thread. Every load request
+//! gets a generation number; starting a newer load (or cancelling) supersedes
+//! all older ones, which are dropped as soon as the loader notices, including
+//! part-way through an HTTP download.
+
+use std::io::{BufReader, Cursor, Read};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use rodio::{Decoder, Source};
+
+use crate::audio::error::AudioError;
+use crate::audio::source::TrackSource;
+
+/// Chunk size for reading HTTP bodies, between cancellation checks
+const READ_CHUNK_BYTES: usize = 64 * 1024;
+
+/// A decoded source ready to append to the sink.
+pub type LoadedSource = Box<dyn Source<Item = i16> + Send>;
+
+struct LoadRequest {
+    generation: u64,
+    source: TrackSource,
+}
+
+/// Outcome of a load, sent back to the audio thread.
+pub struct LoadResult {
+    pub generation: u64,
+    pub result: Result<LoadedSource, AudioError>,
+}
+
+/// Why a load did not produce a source.
+enum LoadError {
+    /// A newer request superseded this one; nothing is reported
+    Superseded,
+    Failed(AudioError),
+}
+
+impl From<AudioError> for LoadError {
+    fn from(err: AudioError) -> Self {
+        LoadError::Failed(err)
+    }
+}
+
+/// Handle to the loader thread, owned by the audio thread.
+pub struct Loader {
+    req_tx: Sender<LoadRequest>,
+    latest: Arc<AtomicU64>,
+}
+
+impl Loader {
+    /// Spawn the loader thread. Results are delivered on `result_tx`.
+    pub fn spawn(result_tx: Sender<LoadResult>) -> Result<Self, String> {
+        let (req_tx, req_rx) = unbounded::<LoadRequest>();
+        let latest = Arc::new(AtomicU64::new(0));
+
+        let latest_clone = latest.clone();
+        thread::Builder::new()
+            .name("lumina-loader".into())
+            .spawn(move || run(req_rx, result_tx, latest_clone))
+            .map_err(|e| format!("Failed to spawn loader thread: {}", e))?;
+
+        Ok(Self { req_tx, latest })
+    }
+
+    /// Start loading `source`, superseding any load in flight. Returns its generation.
+    pub fn load(&self, source: TrackSource) -> u64 {
+        let generation = self.latest.fetch_add(1, Ordering::SeqCst) + 1;
+        let _ = self.req_tx.send(LoadRequest { generation, source });
+        generation
+    }
+
+    /// Abandon any load in flight.
+    pub fn cancel(&self) {
+        self.latest.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn run(req_rx: Receiver<LoadRequest>, result_tx: Sender<LoadResult>, latest: Arc<AtomicU64>) {
+    while let Ok(mut request) = req_rx.recv() {
+        // Skip straight to the newest queued request
+        while let Ok(newer) = req_rx.try_recv() {
+            request = newer;
+        }
+
+        let is_current = || latest.load(Ordering::SeqCst) == request.generation;
+        if !is_current() {
+            continue;
+        }
+
+        let result = match &request.source {
+            TrackSource::LocalFile { path } => load_local_file(path).map_err(LoadError::from),
+            TrackSource::HttpStream { url } => load_http_stream(url, &is_current),
+        };
+
+        let result = match result {
+            Ok(source) => Ok(source),
+            Err(LoadError::Failed(e)) => Err(e),
+            Err(LoadError::Superseded) => {
+                log::debug!("Load superseded: {:?}", request.source);
+                continue;
+            }
+        };
+
+        // The audio thread also checks the generation; this just avoids a wasted send
+        if is_current() {
+            let _ = result_tx.send(LoadResult {
+                generation: request.generation,
+                result,
+            });
+        }
+    }
+    log::debug!("Loader thread shutting down");
+}
+
+fn load_local_file(path: &std::path::Path) -> Result<LoadedSource, AudioError> {
+    log::debug!("Loading local file: {}", path.display());
+
+    let file = std::fs::File::open(path).map_err(|e| AudioError::from_file_error(path, e))?;
+
+    let decoder = Decoder::new(BufReader::new(file))?;
+    Ok(Box::new(decoder))
+}
+
+fn load_http_stream(url: &str, is_current: &dyn Fn() -> bool) -> Result<LoadedSource, LoadError> {
+    log::debug!("Loading HTTP stream: {}", url);
+
+    let mut response =
+        reqwest::blocking::get(url).map_err(|e| AudioError::Network(e.to_string()))?;
+
+    if !response.status().is_success() {
+        return Err(AudioError::HttpStatus {
+            status: response.status().as_u16(),
+        }
+        .into());
+    }
+
+    // Read in chunks so a superseded download stops early
+    let mut bytes = Vec::with_capacity(response.content_length().unwrap_or(0) as usize);
+    let mut chunk = vec![0u8; READ_CHUNK_BYTES];
+    loop {
+        if !is_current() {
+            return Err(LoadError::Superseded);
+        }
+        let read = response
+            .read(&mut chunk)
+            .map_err(|e| AudioError::Network(e.to_string()))?;
+        if read == 0 {
+            break;
+        }
+        bytes.extend_from_slice(&chunk[..read]);
+    }
+
+    log::debug!("Downloaded {} bytes", bytes.len());
+
+    let decoder = Decoder::new(Cursor::new(bytes)).map_err(AudioError::from)?;
+    Ok(Box::new(decoder))
+}
@@ -1,6 +1,8 @@
 pub mod commands;
 pub mod engine;
+pub mod error;
 pub mod events;
+pub mod loader;
 pub mod source;
 pub mod state;
 
@@ -2,6 +2,9 @@ use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+use crate::audio::error::AudioError;
+use crate::audio::source::TrackSource;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackInfo {
     pub id: String,
@@ -34,13 +37,16 @@ impl RepeatMode {
 #[derive(Debug, Clone, Serialize, Default)]
 pub struct AudioState {
     pub current_track: Option<TrackInfo>,
+    /// Where the current track is played from; not sent to clients
+    #[serde(skip)]
+    pub current_source: Option<TrackSource>,
     pub is_playing: bool,
     pub position_secs: f64,
     pub duration_secs: f64,
     pub volume: f32,
     pub is_muted: bool,
     pub is_loading: bool,
-    pub error: Option<String>,
+    pub error: Option<AudioError>,
     pub repeat_mode: RepeatMode,
     pub is_shuffled: bool,
 }
@@ -1,12 +1,19 @@
 mod audio;
+mod mpd;
 #[cfg(feature = "plugins")]
 mod plugins;
+mod random;
+#[cfg(feature = "remote-api")]
+mod remote;
 
 use audio::engine::AudioEngineHandle;
+use mpd::MpdServerState;
 #[cfg(feature = "plugins")]
 use plugins::downloader::DownloaderState;
 #[cfg(feature = "plugins")]
 use plugins::terminal::TerminalState;
+#[cfg(feature = "remote-api")]
+use remote::RemoteApiState;
 use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -19,10 +26,17 @@ pub fn run() {
                 .expect("Failed to initialize audio engine");
             app.manage(engine);
 
+            // MPD server is opt-in; it starts via `mpd_server_start`
+            app.manage(MpdServerState::default());
+
+            // Remote API is opt-in; it starts via `remote_api_start`
+            #[cfg(feature = "remote-api")]
+            app.manage(RemoteApiState::default());
+
             #[cfg(feature = "plugins")]
             {
                 // Initialize downloader state
-                app.manage(DownloaderState::default());
+                app.manage(DownloaderState::load(app.handle()));
                 // Initialize terminal state
                 app.manage(TerminalState::default());
             }
@@ -49,6 +63,15 @@ pub fn run() {
             audio::audio_toggle_shuffle,
             audio::audio_cycle_repeat,
             audio::audio_get_state,
+            mpd::mpd_server_start,
+            mpd::mpd_server_stop,
+            mpd::mpd_server_status,
+            #[cfg(feature = "remote-api")]
+            remote::remote_api_start,
+            #[cfg(feature = "remote-api")]
+            remote::remote_api_stop,
+            #[cfg(feature = "remote-api")]
+            remote::remote_api_status,
             #[cfg(feature = "plugins")]
             plugins::downloader::downloader_search_artist,
             #[cfg(feature = "plugins")]
@@ -56,6 +79,10 @@ pub fn run() {
             #[cfg(feature = "plugins")]
             plugins::downloader::downloader_get_tracklist,
             #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_get_releases,
+            #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_find_owned,
+            #[cfg(feature = "plugins")]
             plugins::downloader::downloader_search_songs,
             #[cfg(feature = "plugins")]
             plugins::downloader::downloader_download_songs,
@@ -68,8 +95,40 @@ pub fn run() {
             #[cfg(feature = "plugins")]
             plugins::downloader::downloader_clear_finished,
             #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_resume,
+            #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_cancel_item,
+            #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_pause_item,
+            #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_move_item,
+            #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_retry_failed,
+            #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_get_config,
+            #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_set_config,
+            #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_preview_paths,
+            #[cfg(feature = "plugins")]
             plugins::downloader::downloader_trigger_scan,
             #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_set_scan_server,
+            #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_import_scan,
+            #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_import_match,
+            #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_import_apply,
+            #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_get_watchlist,
+            #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_follow_artist,
+            #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_unfollow_artist,
+            #[cfg(feature = "plugins")]
+            plugins::downloader::downloader_check_watchlist,
+            #[cfg(feature = "plugins")]
             plugins::terminal::terminal_spawn,
             #[cfg(feature = "plugins")]
             plugins::terminal::terminal_write,
@@ -0,0 +1,61 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::mpd::server::MpdServer;
+
+/// Default listen address; use `0.0.0.0:6600` to accept remotes on the LAN.
+const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1:6600";
+
+/// Tauri-managed slot for the (optional) running MPD server.
+#[derive(Default)]
+pub struct MpdServerState {
+    server: Mutex<Option<MpdServer>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MpdServerStatus {
+    pub running: bool,
+    pub address: Option<String>,
+}
+
+impl MpdServerState {
+    fn status(&self) -> MpdServerStatus {
+        let server = self.server.lock();
+        MpdServerStatus {
+            running: server.is_some(),
+            address: server.as_ref().map(|s| s.address().to_string()),
+        }
+    }
+}
+
+/// Start the MPD server, restarting it if it is already running.
+#[tauri::command]
+pub fn mpd_server_start(
+    app: AppHandle,
+    bind_address: Option<String>,
+    state: State<'_, MpdServerState>,
+) -> Result<MpdServerStatus, String> {
+    let bind_address = bind_address.unwrap_or_else(|| DEFAULT_BIND_ADDRESS.to_string());
+    {
+        let mut server = state.server.lock();
+        if let Some(running) = server.take() {
+            running.stop();
+        }
+        *server = Some(MpdServer::start(app, &bind_address)?);
+    }
+    Ok(state.status())
+}
+
+#[tauri::command]
+pub fn mpd_server_stop(state: State<'_, MpdServerState>) -> MpdServerStatus {
+    if let Some(running) = state.server.lock().take() {
+        running.stop();
+    }
+    state.status()
+}
+
+#[tauri::command]
+pub fn mpd_server_status(state: State<'_, MpdServerState>) -> MpdServerStatus {
+    state.status()
+}
@@ -0,0 +1,8 @@
+//! Optional MPD protocol server so existing MPD clients can control playback.
+
+pub mod commands;
+pub mod protocol;
+pub mod queue;
+pub mod server;
+
+pub use commands::*;
@@ -0,0 +1,287 @@
+//! MPD wire protocol: request tokenizing and response formatting.
+//!
+//! See https://mpd.readthedocs.io/en/latest/protocol.html for the reference.
+
+use std::fmt::Write as _;
+
+/// Protocol version announced in the greeting line.
+pub const PROTOCOL_VERSION: &str = "0.23.5";
+
+/// MPD `ACK` error codes used by this server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AckCode {
+    NotList = 1,
+    Arg = 2,
+    Unknown = 5,
+    NoExist = 50,
+    System = 52,
+}
+
+/// An error reply, formatted as `ACK [code@index] {command} message`.
+#[derive(Debug, Clone)]
+pub struct Ack {
+    pub code: AckCode,
+    pub message: String,
+}
+
+impl Ack {
+    pub fn new(code: AckCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn arg(message: impl Into<String>) -> Self {
+        Self::new(AckCode::Arg, message)
+    }
+
+    pub fn no_exist(message: impl Into<String>) -> Self {
+        Self::new(AckCode::NoExist, message)
+    }
+
+    /// Render the error line for a command at `list_index` within a command list.
+    pub fn to_line(&self, list_index: usize, command: &str) -> String {
+        format!(
+            "ACK [{}@{}] {{{}}} {}\n",
+            self.code as u8, list_index, command, self.message
+        )
+    }
+}
+
+/// A parsed request line: command name plus its arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+impl Request {
+    /// Parse a request line, honouring double quotes and backslash escapes.
+    pub fn parse(line: &str) -> Result<Self, Ack> {
+        let mut tokens = Vec::new();
+        let mut chars = line.trim_end_matches(['\r', '\n']).chars().peekable();
+
+        loop {
+            while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                chars.next();
+            }
+            let Some(&first) = chars.peek() else {
+                break;
+            };
+
+            let mut token = String::new();
+            if first == '"' {
+                chars.next();
+                let mut closed = false;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => match chars.next() {
+                            Some(escaped) => token.push(escaped),
+                            None => break,
+                        },
+                        '"' => {
+                            closed = true;
+                            break;
+                        }
+                        _ => token.push(c),
+                    }
+                }
+                if !closed {
+                    return Err(Ack::arg("Missing closing '\"'"));
+                }
+            } else {
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+            }
+            tokens.push(token);
+        }
+
+        if tokens.is_empty() {
+            return Err(Ack::new(AckCode::Unknown, "No command given"));
+        }
+        let command = tokens.remove(0);
+        Ok(Self {
+            command,
+            args: tokens,
+        })
+    }
+
+    pub fn arg(&self, index: usize) -> Option<&str> {
+        self.args.get(index).map(String::as_str)
+    }
+
+    /// Parse a required argument into `T`.
+    pub fn parse_arg<T: std::str::FromStr>(&self, index: usize) -> Result<T, Ack> {
+        let raw = self
+            .arg(index)
+            .ok_or_else(|| Ack::arg("Missing argument"))?;
+        raw.parse()
+            .map_err(|_| Ack::arg(format!("Invalid argument: {raw}")))
+    }
+
+    /// Parse an optional argument into `T`.
+    pub fn parse_opt_arg<T: std::str::FromStr>(&self, index: usize) -> Result<Option<T>, Ack> {
+        match self.arg(index) {
+            Some(_) => self.parse_arg(index).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Parse a boolean argument given as `0` or `1`.
+    pub fn parse_bool_arg(&self, index: usize) -> Result<bool, Ack> {
+        match self.arg(index) {
+            Some("0") => Ok(false),
+            Some("1") => Ok(true),
+            Some(other) => Err(Ack::arg(format!("Boolean (0/1) expected: {other}"))),
+            None => Err(Ack::arg("Missing argument")),
+        }
+    }
+}
+
+/// Parse a `START:END` range (END optional) or a single position.
+pub fn parse_range(raw: &str) -> Result<(usize, Option<usize>), Ack> {
+    let invalid = || Ack::arg(format!("Invalid range: {raw}"));
+    match raw.split_once(':') {
+        Some((start, "")) => Ok((start.parse().map_err(|_| invalid())?, None)),
+        Some((start, end)) => {
+            let start: usize = start.parse().map_err(|_| invalid())?;
+            let end: usize = end.parse().map_err(|_| invalid())?;
+            if end < start {
+                return Err(invalid());
+            }
+            Ok((start, Some(end)))
+        }
+        None => {
+            let pos: usize = raw.parse().map_err(|_| invalid())?;
+            Ok((pos, Some(pos + 1)))
+        }
+    }
+}
+
+/// Accumulates `key: value` lines for a successful reply.
+#[derive(Debug, Default)]
+pub struct Response {
+    body: String,
+}
+
+impl Response {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn field(&mut self, key: &str, value: impl std::fmt::Display) -> &mut Self {
+        // Values must stay on one line or clients will misparse the reply
+        let value = value.to_string().replace('\n', " ");
+        let _ = writeln!(self.body, "{key}: {value}");
+        self
+    }
+
+    pub fn into_body(self) -> String {
+        self.body
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> (String, Vec<String>) {
+        let request = Request::parse(line).unwrap();
+        (request.command, request.args)
+    }
+
+    #[test]
+    fn parses_bare_and_quoted_arguments() {
+        assert_eq!(parse("status\n"), ("status".into(), vec![]));
+        assert_eq!(
+            parse("  add   \"/music/A Song.mp3\"  3\r\n"),
+            ("add".into(), vec!["/music/A Song.mp3".into(), "3".into()])
+        );
+        assert_eq!(parse("find \"\""), ("find".into(), vec!["".into()]));
+    }
+
+    #[test]
+    fn unescapes_quoted_arguments() {
+        assert_eq!(
+            parse(r#"addtagid 1 title "Say \"Hi\" \\ bye""#),
+            (
+                "addtagid".into(),
+                vec!["1".into(), "title".into(), r#"Say "Hi" \ bye"#.into()]
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_unterminated_quotes_and_empty_lines() {
+        let ack = Request::parse(r#"add "/music/open"#).unwrap_err();
+        assert_eq!(ack.code, AckCode::Arg);
+        let ack = Request::parse(r#"add "trailing escape\"#).unwrap_err();
+        assert_eq!(ack.code, AckCode::Arg);
+        let ack = Request::parse("   \n").unwrap_err();
+        assert_eq!(ack.code, AckCode::Unknown);
+    }
+
+    #[test]
+    fn parses_command_list_lines() {
+        let lines = [
+            "command_list_ok_begin",
+            "add \"a b.mp3\"",
+            "play 0",
+            "command_list_end",
+        ];
+        let commands: Vec<_> = lines.iter().map(|l| parse(l).0).collect();
+        assert_eq!(
+            commands,
+            ["command_list_ok_begin", "add", "play", "command_list_end"]
+        );
+        // Errors inside a list report the failing command's index
+        assert_eq!(
+            Ack::arg("Bad song index").to_line(1, "play"),
+            "ACK [2@1] {play} Bad song index\n"
+        );
+    }
+
+    #[test]
+    fn parses_typed_arguments() {
+        let request = Request::parse("seek 2 x").unwrap();
+        assert_eq!(request.parse_arg::<usize>(0).unwrap(), 2);
+        assert!(request.parse_arg::<f64>(1).is_err());
+        assert!(request.parse_arg::<usize>(2).is_err());
+        assert_eq!(request.parse_opt_arg::<usize>(2).unwrap(), None);
+
+        let request = Request::parse("repeat 1").unwrap();
+        assert!(request.parse_bool_arg(0).unwrap());
+        assert!(Request::parse("repeat 2")
+            .unwrap()
+            .parse_bool_arg(0)
+            .is_err());
+    }
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(parse_range("3").unwrap(), (3, Some(4)));
+        assert_eq!(parse_range("1:4").unwrap(), (1, Some(4)));
+        assert_eq!(parse_range("2:").unwrap(), (2, None));
+        assert_eq!(parse_range("2:2").unwrap(), (2, Some(2)));
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        for raw in ["", ":", ":3", "4:1", "-1", "a:b", "1:x", "1:2:3"] {
+            assert!(parse_range(raw).is_err(), "{raw:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn keeps_response_values_on_one_line() {
+        let mut resp = Response::new();
+        resp.field("Title", "two\nlines").field("Pos", 0);
+        assert_eq!(resp.into_body(), "Title: two lines\nPos: 0\n");
+    }
+}
@@ -0,0 +1,413 @@
+//! The MPD play queue.
+//!
+//! The audio engine plays one track at a time and the frontend keeps its own
+//! queue, so MPD clients get a separate queue that lives in the server. Tracks
+//! started from it carry an `mpd:<id>` track ID so track-end events can be
+//! matched back to the queue for auto-advance.
+
+use std::path::Path;
+
+use crate::audio::state::{RepeatMode, TrackInfo};
+use crate::mpd::protocol::{Ack, Response};
+use crate::random;
+
+const TRACK_ID_PREFIX: &str = "mpd:";
+
+#[derive(Debug, Clone)]
+pub struct QueueEntry {
+    pub id: u32,
+    pub uri: String,
+    pub track: TrackInfo,
+    /// Queue version at which this entry was last added or moved.
+    pub version: u32,
+}
+
+impl QueueEntry {
+    /// Write the song block shared by `currentsong`, `playlistinfo` and friends.
+    pub fn write_song(&self, pos: usize, resp: &mut Response) {
+        write_track(&self.uri, &self.track, resp);
+        resp.field("Pos", pos).field("Id", self.id);
+    }
+}
+
+/// Write the metadata fields of a song block.
+pub fn write_track(uri: &str, track: &TrackInfo, resp: &mut Response) {
+    resp.field("file", uri);
+    if !track.title.is_empty() {
+        resp.field("Title", &track.title);
+    }
+    if !track.artist.is_empty() {
+        resp.field("Artist", &track.artist);
+    }
+    if !track.album.is_empty() {
+        resp.field("Album", &track.album);
+    }
+    if track.duration_secs > 0.0 {
+        resp.field("Time", track.duration_secs.round() as u64)
+            .field("duration", format!("{:.3}", track.duration_secs));
+    }
+}
+
+#[derive(Debug)]
+pub struct PlayQueue {
+    entries: Vec<QueueEntry>,
+    next_id: u32,
+    /// Playlist version, bumped on every change (reported as `playlist` in `status`).
+    version: u32,
+    /// Position of the entry most recently started from the queue.
+    current: Option<usize>,
+}
+
+impl Default for PlayQueue {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            next_id: 1,
+            version: 1,
+            current: None,
+        }
+    }
+}
+
+impl PlayQueue {
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn entries(&self) -> &[QueueEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, pos: usize) -> Option<&QueueEntry> {
+        self.entries.get(pos)
+    }
+
+    pub fn position_of(&self, id: u32) -> Option<usize> {
+        self.entries.iter().position(|e| e.id == id)
+    }
+
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    pub fn set_current(&mut self, pos: Option<usize>) {
+        self.current = pos;
+    }
+
+    /// Map an engine track ID back to a queue position, if it came from the queue.
+    pub fn position_of_track(&self, track_id: &str) -> Option<usize> {
+        let id = track_id.strip_prefix(TRACK_ID_PREFIX)?.parse().ok()?;
+        self.position_of(id)
+    }
+
+    /// Append (or insert at `pos`) a URI and return its song ID.
+    pub fn add(&mut self, uri: &str, pos: Option<usize>) -> Result<u32, Ack> {
+        let pos = pos.unwrap_or(self.entries.len());
+        if pos > self.entries.len() {
+            return Err(Ack::arg("Bad song index"));
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.bump();
+
+        let title = Path::new(uri.split(['?', '#']).next().unwrap_or(uri))
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| uri.to_string());
+        let entry = QueueEntry {
+            id,
+            uri: uri.to_string(),
+            track: TrackInfo {
+                id: format!("{TRACK_ID_PREFIX}{id}"),
+                title,
+                artist: String::new(),
+                album: String::new(),
+                duration_secs: 0.0,
+                cover_url: None,
+            },
+            version: self.version,
+        };
+        self.entries.insert(pos, entry);
+        if let Some(current) = self.current {
+            if pos <= current {
+                self.current = Some(current + 1);
+            }
+        }
+        self.touch_from(pos);
+        Ok(id)
+    }
+
+    /// Set a tag on a queued song (`addtagid`).
+    pub fn set_tag(&mut self, id: u32, tag: &str, value: &str) -> Result<(), Ack> {
+        let pos = self
+            .position_of(id)
+            .ok_or_else(|| Ack::no_exist("No such song"))?;
+        let track = &mut self.entries[pos].track;
+        match tag.to_ascii_lowercase().as_str() {
+            "title" => track.title = value.to_string(),
+            "artist" | "albumartist" => track.artist = value.to_string(),
+            "album" => track.album = value.to_string(),
+            "time" | "duration" => {
+                track.duration_secs = value
+                    .parse()
+                    .map_err(|_| Ack::arg(format!("Invalid duration: {value}")))?
+            }
+            _ => return Err(Ack::arg(format!("Unsupported tag: {tag}"))),
+        }
+        self.bump();
+        self.entries[pos].version = self.version;
+        Ok(())
+    }
+
+    /// Remove the entries in `start..end`.
+    pub fn delete_range(&mut self, start: usize, end: Option<usize>) -> Result<(), Ack> {
+        let end = end.unwrap_or(self.entries.len());
+        if start >= end || end > self.entries.len() {
+            return Err(Ack::arg("Bad song index"));
+        }
+        self.entries.drain(start..end);
+        self.current = match self.current {
+            Some(c) if c >= end => Some(c - (end - start)),
+            Some(c) if c >= start => None,
+            other => other,
+        };
+        self.bump();
+        self.touch_from(start);
+        Ok(())
+    }
+
+    /// Move the entries in `start..end` so the first lands at `to`.
+    pub fn move_range(&mut self, start: usize, end: Option<usize>, to: usize) -> Result<(), Ack> {
+        let end = end.unwrap_or(self.entries.len());
+        let count = end.saturating_sub(start);
+        if count == 0 || end > self.entries.len() || to + count > self.entries.len() {
+            return Err(Ack::arg("Bad song index"));
+        }
+        let current_id = self.current.and_then(|c| self.entries.get(c)).map(|e| e.id);
+        let moved: Vec<_> = self.entries.drain(start..end).collect();
+        for (offset, entry) in moved.into_iter().enumerate() {
+            self.entries.insert(to + offset, entry);
+        }
+        self.current = current_id.and_then(|id| self.position_of(id));
+        self.bump();
+        self.touch_from(start.min(to));
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.current = None;
+        self.bump();
+    }
+
+    /// Pick the position to play after the current one.
+    ///
+    /// `manual` is true for an explicit `next`, which skips past the current
+    /// song even in repeat-one mode.
+    pub fn next_position(&self, repeat: RepeatMode, shuffled: bool, manual: bool) -> Option<usize> {
+        let len = self.entries.len();
+        if len == 0 {
+            return None;
+        }
+        let Some(current) = self.current else {
+            return Some(0);
+        };
+        if repeat == RepeatMode::One && !manual {
+            return Some(current);
+        }
+        if shuffled && len > 1 {
+            // Any position except the current one
+            let offset = 1 + (random::next_u64() % (len as u64 - 1)) as usize;
+            return Some((current + offset) % len);
+        }
+        if current + 1 < len {
+            Some(current + 1)
+        } else if repeat != RepeatMode::Off {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    /// Pick the position to play before the current one.
+    pub fn previous_position(&self, repeat: RepeatMode) -> Option<usize> {
+        match self.current {
+            Some(0) if repeat != RepeatMode::Off => self.entries.len().checked_sub(1),
+            Some(0) | None => None,
+            Some(current) => Some(current - 1),
+        }
+    }
+
+    fn bump(&mut self) {
+        self.version = self.version.wrapping_add(1);
+    }
+
+    /// Mark every entry from `pos` onward as changed in the current version.
+    fn touch_from(&mut self, pos: usize) {
+        let version = self.version;
+        for entry in self.entries.iter_mut().skip(pos) {
+            entry.version = version;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A queue of `a`, `b`, ... with `current` set.
+    fn queue_of(len: usize, current: Option<usize>) -> PlayQueue {
+        let mut queue = PlayQueue::default();
+        for i in 0..len {
+            let name = (b'a' + i as u8) as char;
+            queue.add(&format!("/music/{name}.mp3"), None).unwrap();
+        }
+        queue.set_current(current);
+        queue
+    }
+
+    fn titles(queue: &PlayQueue) -> String {
+        queue
+            .entries()
+            .iter()
+            .map(|e| e.track.title.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn add_appends_or_inserts_and_keeps_current() {
+        let mut queue = queue_of(3, Some(1));
+        let version = queue.version();
+        let id = queue.add("http://host/x.flac?token=1", Some(0)).unwrap();
+
+        assert_eq!(titles(&queue), "xabc");
+        assert_eq!(queue.get(0).unwrap().track.id, format!("mpd:{id}"));
+        assert_eq!(queue.position_of_track(&format!("mpd:{id}")), Some(0));
+        assert_eq!(queue.current(), Some(2));
+        assert!(queue.version() > version);
+
+        queue.add("/music/y.mp3", Some(4)).unwrap();
+        assert_eq!(titles(&queue), "xabcy");
+        assert_eq!(queue.current(), Some(2));
+        assert!(queue.add("/music/z.mp3", Some(9)).is_err());
+    }
+
+    #[test]
+    fn delete_before_current_shifts_it() {
+        let mut queue = queue_of(5, Some(3));
+        queue.delete_range(0, Some(2)).unwrap();
+        assert_eq!(titles(&queue), "cde");
+        assert_eq!(queue.current(), Some(1));
+    }
+
+    #[test]
+    fn deleting_current_clears_it() {
+        let mut queue = queue_of(5, Some(2));
+        queue.delete_range(1, Some(3)).unwrap();
+        assert_eq!(titles(&queue), "ade");
+        assert_eq!(queue.current(), None);
+
+        let mut queue = queue_of(3, Some(2));
+        queue.delete_range(1, None).unwrap();
+        assert_eq!(titles(&queue), "a");
+        assert_eq!(queue.current(), None);
+    }
+
+    #[test]
+    fn delete_after_current_keeps_it() {
+        let mut queue = queue_of(4, Some(1));
+        queue.delete_range(2, Some(3)).unwrap();
+        assert_eq!(titles(&queue), "abd");
+        assert_eq!(queue.current(), Some(1));
+    }
+
+    #[test]
+    fn rejects_bad_delete_ranges() {
+        let mut queue = queue_of(3, None);
+        assert!(queue.delete_range(2, Some(2)).is_err());
+        assert!(queue.delete_range(1, Some(4)).is_err());
+        assert!(queue.delete_range(3, None).is_err());
+        assert_eq!(titles(&queue), "abc");
+    }
+
+    #[test]
+    fn move_follows_the_current_song() {
+        // The current song itself moves
+        let mut queue = queue_of(5, Some(1));
+        queue.move_range(1, Some(2), 3).unwrap();
+        assert_eq!(titles(&queue), "acdbe");
+        assert_eq!(queue.current(), Some(3));
+
+        // A block moves from before the current song to after it
+        let mut queue = queue_of(5, Some(2));
+        queue.move_range(0, Some(2), 3).unwrap();
+        assert_eq!(titles(&queue), "cdeab");
+        assert_eq!(queue.current(), Some(0));
+
+        // A block moves from after the current song to before it
+        let mut queue = queue_of(5, Some(1));
+        queue.move_range(3, Some(5), 0).unwrap();
+        assert_eq!(titles(&queue), "deabc");
+        assert_eq!(queue.current(), Some(3));
+    }
+
+    #[test]
+    fn rejects_bad_moves() {
+        let mut queue = queue_of(3, Some(0));
+        assert!(queue.move_range(1, Some(1), 0).is_err());
+        assert!(queue.move_range(1, Some(3), 2).is_err());
+        assert!(queue.move_range(0, Some(4), 0).is_err());
+        assert_eq!(titles(&queue), "abc");
+    }
+
+    #[test]
+    fn next_follows_repeat_mode() {
+        let queue = queue_of(3, None);
+        assert_eq!(queue.next_position(RepeatMode::Off, false, false), Some(0));
+
+        let queue = queue_of(3, Some(2));
+        assert_eq!(queue.next_position(RepeatMode::Off, false, false), None);
+        assert_eq!(queue.next_position(RepeatMode::All, false, false), Some(0));
+        assert_eq!(queue.next_position(RepeatMode::One, false, false), Some(2));
+        // An explicit `next` leaves the song even in repeat-one
+        assert_eq!(queue.next_position(RepeatMode::One, false, true), Some(0));
+
+        assert_eq!(
+            PlayQueue::default().next_position(RepeatMode::All, false, false),
+            None
+        );
+    }
+
+    #[test]
+    fn shuffled_next_never_repeats_the_current_song() {
+        let queue = queue_of(4, Some(2));
+        for _ in 0..200 {
+            let next = queue.next_position(RepeatMode::Off, true, false).unwrap();
+            assert!(next < 4 && next != 2);
+        }
+        // Nothing else to pick from
+        let queue = queue_of(1, Some(0));
+        assert_eq!(queue.next_position(RepeatMode::All, true, false), Some(0));
+    }
+
+    #[test]
+    fn previous_wraps_only_with_repeat() {
+        assert_eq!(
+            queue_of(3, Some(0)).previous_position(RepeatMode::Off),
+            None
+        );
+        assert_eq!(
+            queue_of(3, Some(0)).previous_position(RepeatMode::All),
+            Some(2)
+        );
+        assert_eq!(
+            queue_of(3, Some(2)).previous_position(RepeatMode::Off),
+            Some(1)
+        );
+    }
+}
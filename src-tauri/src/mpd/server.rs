@@ -0,0 +1,886 @@
+//! TCP listener, client sessions and idle notifications for the MPD server.
+//!
+//! Architecture:
+//! - An accept thread hands every connection to its own session thread
+//! - Each session has a reader thread feeding request lines through a channel,
+//!   so the session can wait on requests and idle notifications at once
+//! - A notifier thread subscribes to engine events, turns them into MPD idle
+//!   subsystems and advances the MPD queue when one of its tracks ends
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{select, unbounded, Receiver, RecvTimeoutError, Sender};
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager, State};
+
+use crate::audio::engine::AudioEngineHandle;
+use crate::audio::events::{AudioStateEvent, EngineEvent};
+use crate::audio::state::{AudioState, RepeatMode};
+use crate::mpd::protocol::{parse_range, Ack, AckCode, Request, Response, PROTOCOL_VERSION};
+use crate::mpd::queue::{write_track, PlayQueue};
+
+/// How often blocking loops check for shutdown
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Every subsystem name MPD clients may pass to `idle`.
+const IDLE_SUBSYSTEMS: &[&str] = &[
+    "database",
+    "update",
+    "stored_playlist",
+    "playlist",
+    "player",
+    "mixer",
+    "output",
+    "options",
+    "partition",
+    "sticker",
+    "subscription",
+    "message",
+    "neighbor",
+    "mount",
+];
+
+/// Commands reported by `commands`.
+const SUPPORTED_COMMANDS: &[&str] = &[
+    "add",
+    "addid",
+    "addtagid",
+    "clear",
+    "clearerror",
+    "close",
+    "commands",
+    "consume",
+    "currentsong",
+    "decoders",
+    "delete",
+    "deleteid",
+    "getvol",
+    "idle",
+    "listplaylists",
+    "lsinfo",
+    "move",
+    "moveid",
+    "next",
+    "noidle",
+    "notcommands",
+    "outputs",
+    "pause",
+    "ping",
+    "play",
+    "playid",
+    "playlistid",
+    "playlistinfo",
+    "plchanges",
+    "plchangesposid",
+    "previous",
+    "random",
+    "repeat",
+    "replay_gain_status",
+    "seek",
+    "seekcur",
+    "seekid",
+    "setvol",
+    "single",
+    "stats",
+    "status",
+    "stop",
+    "tagtypes",
+    "urlhandlers",
+    "volume",
+];
+
+/// Idle subsystems this server raises.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Subsystem {
+    Player,
+    Mixer,
+    Options,
+    Playlist,
+}
+
+impl Subsystem {
+    fn name(self) -> &'static str {
+        match self {
+            Subsystem::Player => "player",
+            Subsystem::Mixer => "mixer",
+            Subsystem::Options => "options",
+            Subsystem::Playlist => "playlist",
+        }
+    }
+}
+
+/// Fan-out of idle notifications to connected sessions.
+#[derive(Default)]
+struct IdleHub {
+    listeners: Mutex<Vec<Sender<Subsystem>>>,
+}
+
+impl IdleHub {
+    fn subscribe(&self) -> Receiver<Subsystem> {
+        let (tx, rx) = unbounded();
+        self.listeners.lock().push(tx);
+        rx
+    }
+
+    fn notify(&self, subsystem: Subsystem) {
+        self.listeners
+            .lock()
+            .retain(|tx| tx.send(subsystem).is_ok());
+    }
+}
+
+/// State shared by the accept, notifier and session threads.
+struct Shared {
+    app: AppHandle,
+    queue: Mutex<PlayQueue>,
+    idle: IdleHub,
+    shutdown: AtomicBool,
+    clients: Mutex<HashMap<u64, TcpStream>>,
+    next_client_id: AtomicU64,
+    started: Instant,
+    /// Id of the track the queue last started, while it is still the one
+    /// playing. Only that track's end advances the queue; tracks started by
+    /// the app's own player are left for the app to advance.
+    queue_track: Mutex<Option<String>>,
+}
+
+impl Shared {
+    fn engine(&self) -> State<'_, AudioEngineHandle> {
+        self.app.state::<AudioEngineHandle>()
+    }
+
+    /// Start the queue entry at `pos`.
+    fn play_position(&self, queue: &mut PlayQueue, pos: usize) -> Result<(), Ack> {
+        let entry = queue
+            .get(pos)
+            .cloned()
+            .ok_or_else(|| Ack::arg("Bad song index"))?;
+        queue.set_current(Some(pos));
+        *self.queue_track.lock() = Some(entry.track.id.clone());
+        self.engine()
+            .play_track(entry.track, &entry.uri)
+            .map_err(|e| Ack::new(AckCode::System, e.to_string()))
+    }
+
+    /// Queue position of the track the engine is currently playing, if it came from the queue.
+    fn playing_position(&self, queue: &PlayQueue, state: &AudioState) -> Option<usize> {
+        state
+            .current_track
+            .as_ref()
+            .and_then(|t| queue.position_of_track(&t.id))
+    }
+}
+
+/// A running MPD server.
+pub struct MpdServer {
+    shared: Arc<Shared>,
+    address: SocketAddr,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl MpdServer {
+    /// Bind `bind_address` and start serving MPD clients.
+    pub fn start(app: AppHandle, bind_address: &str) -> Result<Self, String> {
+        let listener = TcpListener::bind(bind_address)
+            .map_err(|e| format!("Failed to bind {bind_address}: {e}"))?;
+        let address = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to read listener address: {e}"))?;
+        // Non-blocking accept so the thread can notice shutdown
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("Failed to configure listener: {e}"))?;
+
+        let shared = Arc::new(Shared {
+            app,
+            queue: Mutex::new(PlayQueue::default()),
+            idle: IdleHub::default(),
+            shutdown: AtomicBool::new(false),
+            clients: Mutex::new(HashMap::new()),
+            next_client_id: AtomicU64::new(0),
+            started: Instant::now(),
+            queue_track: Mutex::new(None),
+        });
+
+        let events = shared.engine().subscribe();
+        let notifier_shared = shared.clone();
+        let notifier = thread::Builder::new()
+            .name("lumina-mpd-notify".into())
+            .spawn(move || run_notifier(notifier_shared, events))
+            .map_err(|e| format!("Failed to spawn MPD notifier thread: {e}"))?;
+
+        let accept_shared = shared.clone();
+        let acceptor = thread::Builder::new()
+            .name("lumina-mpd".into())
+            .spawn(move || run_acceptor(accept_shared, listener))
+            .map_err(|e| format!("Failed to spawn MPD server thread: {e}"))?;
+
+        log::info!("MPD server listening on {}", address);
+        Ok(Self {
+            shared,
+            address,
+            threads: vec![notifier, acceptor],
+        })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Stop accepting connections and disconnect every client.
+    pub fn stop(mut self) {
+        self.shared.shutdown.store(true, Ordering::Relaxed);
+        for stream in self.shared.clients.lock().values() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+        for handle in self.threads.drain(..) {
+            let _ = handle.join();
+        }
+        log::info!("MPD server on {} stopped", self.address);
+    }
+}
+
+fn run_acceptor(shared: Arc<Shared>, listener: TcpListener) {
+    while !shared.shutdown.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, peer)) => {
+                log::debug!("MPD client connected: {}", peer);
+                let session_shared = shared.clone();
+                let spawned = thread::Builder::new()
+                    .name("lumina-mpd-client".into())
+                    .spawn(move || Session::run(session_shared, stream));
+                if let Err(e) = spawned {
+                    log::warn!("Failed to spawn MPD client thread: {}", e);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                thread::sleep(POLL_INTERVAL);
+            }
+            Err(e) => {
+                log::warn!("MPD accept failed: {}", e);
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+/// Translate engine events into idle notifications and drive queue auto-advance.
+fn run_notifier(shared: Arc<Shared>, events: Receiver<EngineEvent>) {
+    let mut last = AudioStateEvent::from(&shared.engine().get_state());
+
+    while !shared.shutdown.load(Ordering::Relaxed) {
+        let event = match events.recv_timeout(POLL_INTERVAL) {
+            Ok(event) => event,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+
+        match event {
+            EngineEvent::State(state) => {
+                // Position updates arrive at ~4Hz, so only report fields MPD clients care about
+                let track_id = |s: &AudioStateEvent| s.current_track.as_ref().map(|t| t.id.clone());
+                if state.is_playing != last.is_playing
+                    || state.is_loading != last.is_loading
+                    || state.error != last.error
+                    || track_id(&state) != track_id(&last)
+                {
+                    shared.idle.notify(Subsystem::Player);
+                }
+                if state.volume != last.volume || state.is_muted != last.is_muted {
+                    shared.idle.notify(Subsystem::Mixer);
+                }
+                if state.repeat_mode != last.repeat_mode || state.is_shuffled != last.is_shuffled {
+                    shared.idle.notify(Subsystem::Options);
+                }
+                last = state;
+            }
+            EngineEvent::TrackChanged(changed) => {
+                let mut queue_track = shared.queue_track.lock();
+                if queue_track.as_deref() != Some(changed.track.id.as_str()) {
+                    *queue_track = None;
+                }
+                drop(queue_track);
+                let mut queue = shared.queue.lock();
+                if let Some(pos) = queue.position_of_track(&changed.track.id) {
+                    queue.set_current(Some(pos));
+                }
+                shared.idle.notify(Subsystem::Player);
+            }
+            EngineEvent::TrackEnded(ended) => {
+                shared.idle.notify(Subsystem::Player);
+                if shared.queue_track.lock().as_deref() != Some(ended.track_id.as_str()) {
+                    continue;
+                }
+                let mut queue = shared.queue.lock();
+                if let Some(pos) = queue.position_of_track(&ended.track_id) {
+                    queue.set_current(Some(pos));
+                    if let Some(next) =
+                        queue.next_position(last.repeat_mode, last.is_shuffled, false)
+                    {
+                        if let Err(e) = shared.play_position(&mut queue, next) {
+                            log::warn!("MPD auto-advance failed: {}", e.message);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A pending `command_list_begin` / `command_list_ok_begin` block.
+struct CommandList {
+    ok_mode: bool,
+    requests: Vec<Request>,
+}
+
+/// One connected MPD client.
+struct Session {
+    shared: Arc<Shared>,
+    writer: TcpStream,
+    /// Subsystems changed since the client last consumed them with `idle`
+    pending: BTreeSet<Subsystem>,
+    /// Set while the client is waiting in `idle`; empty means "any subsystem"
+    idle_filter: Option<Vec<String>>,
+    command_list: Option<CommandList>,
+}
+
+impl Session {
+    fn run(shared: Arc<Shared>, stream: TcpStream) {
+        let client_id = shared.next_client_id.fetch_add(1, Ordering::Relaxed);
+        // Accepted sockets inherit the listener's non-blocking mode on some platforms
+        if let Err(e) = stream.set_nonblocking(false) {
+            log::warn!("Failed to configure MPD client stream: {}", e);
+            return;
+        }
+        let reader = match stream.try_clone() {
+            Ok(s) => s,
+            Err(e) => {
+                log::warn!("Failed to clone MPD client stream: {}", e);
+                return;
+            }
+        };
+        if let Ok(s) = stream.try_clone() {
+            shared.clients.lock().insert(client_id, s);
+        }
+
+        // Feed request lines through a channel so idle notifications can interleave
+        let (line_tx, line_rx) = unbounded::<String>();
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                match line {
+                    Ok(line) => {
+                        if line_tx.send(line).is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+        });
+
+        let idle_rx = shared.idle.subscribe();
+        let mut session = Self {
+            shared: shared.clone(),
+            writer: stream,
+            pending: BTreeSet::new(),
+            idle_filter: None,
+            command_list: None,
+        };
+
+        if session.write(&format!("OK MPD {PROTOCOL_VERSION}\n")) {
+            session.serve(line_rx, idle_rx);
+        }
+
+        shared.clients.lock().remove(&client_id);
+        let _ = session.writer.shutdown(Shutdown::Both);
+        log::debug!("MPD client disconnected");
+    }
+
+    fn serve(&mut self, line_rx: Receiver<String>, idle_rx: Receiver<Subsystem>) {
+        loop {
+            select! {
+                recv(line_rx) -> line => match line {
+                    Ok(line) => {
+                        if !self.handle_line(&line) {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                },
+                recv(idle_rx) -> subsystem => {
+                    if let Ok(subsystem) = subsystem {
+                        self.pending.insert(subsystem);
+                        if self.idle_filter.is_some() && !self.flush_idle(false) {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Handle one request line. Returns false when the connection should close.
+    fn handle_line(&mut self, line: &str) -> bool {
+        // While idling, only `noidle` is allowed
+        if self.idle_filter.is_some() {
+            return line.trim() == "noidle" && self.flush_idle(true);
+        }
+
+        let request = match Request::parse(line) {
+            Ok(r) => r,
+            Err(ack) => return self.write(&ack.to_line(0, "")),
+        };
+
+        if let Some(list) = self.command_list.as_mut() {
+            if request.command == "command_list_end" {
+                let list = self.command_list.take().unwrap();
+                return self.run_command_list(list);
+            }
+            list.requests.push(request);
+            return true;
+        }
+
+        match request.command.as_str() {
+            "command_list_begin" | "command_list_ok_begin" => {
+                self.command_list = Some(CommandList {
+                    ok_mode: request.command == "command_list_ok_begin",
+                    requests: Vec::new(),
+                });
+                true
+            }
+            "command_list_end" => {
+                let ack = Ack::new(AckCode::NotList, "not in command list mode");
+                self.write(&ack.to_line(0, &request.command))
+            }
+            "close" => false,
+            // A stray noidle outside idle is ignored, as MPD does
+            "noidle" => true,
+            "idle" => {
+                let unknown = request
+                    .args
+                    .iter()
+                    .find(|name| !IDLE_SUBSYSTEMS.contains(&name.as_str()));
+                if let Some(name) = unknown {
+                    let ack = Ack::arg(format!("Unrecognized idle event: {name}"));
+                    return self.write(&ack.to_line(0, "idle"));
+                }
+                self.idle_filter = Some(request.args.clone());
+                self.flush_idle(false)
+            }
+            _ => match dispatch(&self.shared, &request) {
+                Ok(resp) => self.write(&(resp.into_body() + "OK\n")),
+                Err(ack) => self.write(&ack.to_line(0, &request.command)),
+            },
+        }
+    }
+
+    fn run_command_list(&mut self, list: CommandList) -> bool {
+        let mut out = String::new();
+        for (index, request) in list.requests.iter().enumerate() {
+            match dispatch(&self.shared, request) {
+                Ok(resp) => {
+                    out.push_str(&resp.into_body());
+                    if list.ok_mode {
+                        out.push_str("list_OK\n");
+                    }
+                }
+                Err(ack) => {
+                    out.push_str(&ack.to_line(index, &request.command));
+                    return self.write(&out);
+                }
+            }
+        }
+        out.push_str("OK\n");
+        self.write(&out)
+    }
+
+    /// Answer a waiting `idle` with matching pending subsystems.
+    ///
+    /// With `force` (from `noidle`) the reply is sent even if nothing changed.
+    /// Returns false when the write failed.
+    fn flush_idle(&mut self, force: bool) -> bool {
+        let Some(filter) = self.idle_filter.as_ref() else {
+            return true;
+        };
+        let matching: Vec<Subsystem> = self
+            .pending
+            .iter()
+            .copied()
+            .filter(|s| filter.is_empty() || filter.iter().any(|f| f == s.name()))
+            .collect();
+        if matching.is_empty() && !force {
+            return true;
+        }
+
+        let mut resp = Response::new();
+        for subsystem in &matching {
+            self.pending.remove(subsystem);
+            resp.field("changed", subsystem.name());
+        }
+        self.idle_filter = None;
+        self.write(&(resp.into_body() + "OK\n"))
+    }
+
+    fn write(&mut self, data: &str) -> bool {
+        self.writer.write_all(data.as_bytes()).is_ok() && self.writer.flush().is_ok()
+    }
+}
+
+/// Execute a single request against the engine and the MPD queue.
+fn dispatch(shared: &Shared, req: &Request) -> Result<Response, Ack> {
+    let engine = shared.engine();
+    let mut resp = Response::new();
+
+    match req.command.as_str() {
+        "ping" | "clearerror" | "password" | "binarylimit" | "tagtypes" => {
+            if req.command == "tagtypes" && req.args.is_empty() {
+                for tag in ["Artist", "Album", "Title"] {
+                    resp.field("tagtype", tag);
+                }
+            }
+        }
+        "commands" => {
+            for command in SUPPORTED_COMMANDS {
+                resp.field("command", command);
+            }
+        }
+        "notcommands" | "decoders" | "lsinfo" | "listplaylists" => {}
+        "urlhandlers" => {
+            resp.field("handler", "http://")
+                .field("handler", "https://");
+        }
+        "outputs" => {
+            resp.field("outputid", 0)
+                .field("outputname", "GuteMusik")
+                .field("plugin", "rodio")
+                .field("outputenabled", 1);
+        }
+        "replay_gain_status" => {
+            resp.field("replay_gain_mode", "off");
+        }
+        "stats" => {
+            resp.field("artists", 0)
+                .field("albums", 0)
+                .field("songs", shared.queue.lock().len())
+                .field("uptime", shared.started.elapsed().as_secs())
+                .field("playtime", 0)
+                .field("db_playtime", 0)
+                .field("db_update", 0);
+        }
+
+        // ── Status ──────────────────────────────────────────────────────────
+        "status" => {
+            let state = engine.get_state();
+            let queue = shared.queue.lock();
+            let player_state = if state.current_track.is_none() {
+                "stop"
+            } else if state.is_playing || state.is_loading {
+                "play"
+            } else {
+                "pause"
+            };
+
+            resp.field("volume", mixer_volume(&state))
+                .field("repeat", (state.repeat_mode != RepeatMode::Off) as u8)
+                .field("random", state.is_shuffled as u8)
+                .field("single", (state.repeat_mode == RepeatMode::One) as u8)
+                .field("consume", 0)
+                .field("playlist", queue.version())
+                .field("playlistlength", queue.len())
+                .field("state", player_state);
+            if let Some(pos) = shared.playing_position(&queue, &state) {
+                resp.field("song", pos)
+                    .field("songid", queue.entries()[pos].id);
+            }
+            if player_state != "stop" {
+                resp.field(
+                    "time",
+                    format!(
+                        "{}:{}",
+                        state.position_secs.round() as u64,
+                        state.duration_secs.round() as u64
+                    ),
+                )
+                .field("elapsed", format!("{:.3}", state.position_secs))
+                .field("duration", format!("{:.3}", state.duration_secs));
+            }
+            if let Some(error) = &state.error {
+                resp.field("error", error);
+            }
+        }
+        "currentsong" => {
+            let state = engine.get_state();
+            let queue = shared.queue.lock();
+            if let Some(pos) = shared.playing_position(&queue, &state) {
+                queue.entries()[pos].write_song(pos, &mut resp);
+            } else if let Some(track) = &state.current_track {
+                // Started from the app itself rather than the MPD queue
+                write_track(&track.id, track, &mut resp);
+            }
+        }
+
+        // ── Playback ────────────────────────────────────────────────────────
+        "play" => {
+            let mut queue = shared.queue.lock();
+            match req.parse_opt_arg::<usize>(0)? {
+                Some(pos) => shared.play_position(&mut queue, pos)?,
+                None => {
+                    let state = engine.get_state();
+                    if state.current_track.is_some() {
+                        engine.resume();
+                    } else if queue.len() > 0 {
+                        let pos = queue.current().filter(|&p| p < queue.len()).unwrap_or(0);
+                        shared.play_position(&mut queue, pos)?;
+                    }
+                }
+            }
+        }
+        "playid" => {
+            let mut queue = shared.queue.lock();
+            match req.parse_opt_arg::<u32>(0)? {
+                Some(id) => {
+                    let pos = queue
+                        .position_of(id)
+                        .ok_or_else(|| Ack::no_exist("No such song"))?;
+                    shared.play_position(&mut queue, pos)?;
+                }
+                None => engine.resume(),
+            }
+        }
+        "pause" => match req.arg(0) {
+            Some(_) => {
+                if req.parse_bool_arg(0)? {
+                    engine.pause();
+                } else {
+                    engine.resume();
+                }
+            }
+            None => engine.toggle_play(),
+        },
+        "stop" => engine.stop(),
+        "next" | "previous" => {
+            let state = engine.get_state();
+            let mut queue = shared.queue.lock();
+            if let Some(pos) = shared.playing_position(&queue, &state) {
+                queue.set_current(Some(pos));
+            }
+            let target = if req.command == "next" {
+                queue.next_position(state.repeat_mode, state.is_shuffled, true)
+            } else {
+                queue.previous_position(state.repeat_mode)
+            };
+            match target {
+                Some(pos) => shared.play_position(&mut queue, pos)?,
+                None => engine.stop(),
+            }
+        }
+        "seek" | "seekid" => {
+            let time = parse_time(req, 1)?;
+            let state = engine.get_state();
+            let mut queue = shared.queue.lock();
+            let pos = if req.command == "seek" {
+                req.parse_arg::<usize>(0)?
+            } else {
+                queue
+                    .position_of(req.parse_arg(0)?)
+                    .ok_or_else(|| Ack::no_exist("No such song"))?
+            };
+            if shared.playing_position(&queue, &state) != Some(pos) {
+                shared.play_position(&mut queue, pos)?;
+            }
+            engine.seek(time);
+        }
+        "seekcur" => {
+            let raw = req.arg(0).ok_or_else(|| Ack::arg("Missing argument"))?;
+            let value = parse_time(req, 0)?;
+            let target = if raw.starts_with('+') || raw.starts_with('-') {
+                engine.get_state().position_secs + value
+            } else {
+                value
+            };
+            engine.seek(target.max(0.0));
+        }
+
+        // ── Mixer & options ─────────────────────────────────────────────────
+        "setvol" => {
+            let volume: u8 = req.parse_arg(0)?;
+            if volume > 100 {
+                return Err(Ack::arg("Invalid volume value"));
+            }
+            engine.set_volume(volume as f32 / 100.0);
+        }
+        "volume" => {
+            let change: i32 = req.parse_arg(0)?;
+            let current = mixer_volume(&engine.get_state()) as i32;
+            engine.set_volume((current + change).clamp(0, 100) as f32 / 100.0);
+        }
+        "getvol" => {
+            resp.field("volume", mixer_volume(&engine.get_state()));
+        }
+        "repeat" => {
+            let enabled = req.parse_bool_arg(0)?;
+            let mode = match (enabled, engine.get_state().repeat_mode) {
+                (false, _) => RepeatMode::Off,
+                (true, RepeatMode::One) => RepeatMode::One,
+                (true, _) => RepeatMode::All,
+            };
+            engine.set_repeat(mode);
+        }
+        "single" => {
+            // `single oneshot` has no engine equivalent
+            let enabled = req.parse_bool_arg(0)?;
+            let mode = match (enabled, engine.get_state().repeat_mode) {
+                (true, _) => RepeatMode::One,
+                (false, RepeatMode::One) => RepeatMode::All,
+                (false, current) => current,
+            };
+            engine.set_repeat(mode);
+        }
+        "random" => engine.set_shuffle(req.parse_bool_arg(0)?),
+        "consume" => {
+            if req.parse_bool_arg(0)? {
+                return Err(Ack::arg("Consume mode is not supported"));
+            }
+        }
+
+        // ── Queue ───────────────────────────────────────────────────────────
+        "add" | "addid" => {
+            let uri = req
+                .arg(0)
+                .filter(|u| !u.is_empty())
+                .ok_or_else(|| Ack::arg("Missing URI"))?;
+            let pos = if req.command == "addid" {
+                req.parse_opt_arg::<usize>(1)?
+            } else {
+                None
+            };
+            let id = shared.queue.lock().add(uri, pos)?;
+            if req.command == "addid" {
+                resp.field("Id", id);
+            }
+            shared.idle.notify(Subsystem::Playlist);
+        }
+        "addtagid" => {
+            let id: u32 = req.parse_arg(0)?;
+            let tag = req.arg(1).ok_or_else(|| Ack::arg("Missing tag"))?;
+            let value = req.arg(2).ok_or_else(|| Ack::arg("Missing value"))?;
+            shared.queue.lock().set_tag(id, tag, value)?;
+            shared.idle.notify(Subsystem::Playlist);
+        }
+        "clear" => {
+            let state = engine.get_state();
+            let mut queue = shared.queue.lock();
+            if shared.playing_position(&queue, &state).is_some() {
+                engine.stop();
+            }
+            queue.clear();
+            shared.idle.notify(Subsystem::Playlist);
+        }
+        "delete" => {
+            let raw = req.arg(0).ok_or_else(|| Ack::arg("Missing argument"))?;
+            let (start, end) = parse_range(raw)?;
+            shared.queue.lock().delete_range(start, end)?;
+            shared.idle.notify(Subsystem::Playlist);
+        }
+        "deleteid" => {
+            let mut queue = shared.queue.lock();
+            let pos = queue
+                .position_of(req.parse_arg(0)?)
+                .ok_or_else(|| Ack::no_exist("No such song"))?;
+            queue.delete_range(pos, Some(pos + 1))?;
+            shared.idle.notify(Subsystem::Playlist);
+        }
+        "move" => {
+            let raw = req.arg(0).ok_or_else(|| Ack::arg("Missing argument"))?;
+            let (start, end) = parse_range(raw)?;
+            let to: usize = req.parse_arg(1)?;
+            shared.queue.lock().move_range(start, end, to)?;
+            shared.idle.notify(Subsystem::Playlist);
+        }
+        "moveid" => {
+            let mut queue = shared.queue.lock();
+            let pos = queue
+                .position_of(req.parse_arg(0)?)
+                .ok_or_else(|| Ack::no_exist("No such song"))?;
+            queue.move_range(pos, Some(pos + 1), req.parse_arg(1)?)?;
+            shared.idle.notify(Subsystem::Playlist);
+        }
+        "playlistinfo" => {
+            let queue = shared.queue.lock();
+            let (start, end) = match req.arg(0) {
+                Some(raw) => parse_range(raw)?,
+                None => (0, None),
+            };
+            let end = end.unwrap_or(queue.len()).min(queue.len());
+            if req.arg(0).is_some() && start >= queue.len() {
+                return Err(Ack::arg("Bad song index"));
+            }
+            for pos in start..end {
+                queue.entries()[pos].write_song(pos, &mut resp);
+            }
+        }
+        "playlistid" => {
+            let queue = shared.queue.lock();
+            match req.parse_opt_arg::<u32>(0)? {
+                Some(id) => {
+                    let pos = queue
+                        .position_of(id)
+                        .ok_or_else(|| Ack::no_exist("No such song"))?;
+                    queue.entries()[pos].write_song(pos, &mut resp);
+                }
+                None => {
+                    for (pos, entry) in queue.entries().iter().enumerate() {
+                        entry.write_song(pos, &mut resp);
+                    }
+                }
+            }
+        }
+        "plchanges" | "plchangesposid" => {
+            let since: u32 = req.parse_arg(0)?;
+            let queue = shared.queue.lock();
+            for (pos, entry) in queue.entries().iter().enumerate() {
+                if entry.version <= since {
+                    continue;
+                }
+                if req.command == "plchanges" {
+                    entry.write_song(pos, &mut resp);
+                } else {
+                    resp.field("cpos", pos).field("Id", entry.id);
+                }
+            }
+        }
+
+        _ => {
+            return Err(Ack::new(
+                AckCode::Unknown,
+                format!("unknown command \"{}\"", req.command),
+            ))
+        }
+    }
+
+    Ok(resp)
+}
+
+/// Engine volume as the 0-100 mixer value MPD reports.
+fn mixer_volume(state: &AudioState) -> u8 {
+    if state.is_muted {
+        0
+    } else {
+        (state.volume * 100.0).round() as u8
+    }
+}
+
+/// Parse a time in seconds, refusing NaN and infinities.
+fn parse_time(req: &Request, index: usize) -> Result<f64, Ack> {
+    let time: f64 = req.parse_arg(index)?;
+    if !time.is_finite() {
+        return Err(Ack::arg(format!("Invalid time: {time}")));
+    }
+    Ok(time)
+}
@@ -0,0 +1,239 @@
+//! Album artwork from the Cover Art Archive.
+//!
+//! The front cover is fetched at the configured size and embedded in every
+//! track, scaled down first if `embedded_cover_max_px` is set. Albums also get
+//! it as a file next to their tracks (`cover.jpg` by default), which is what
+//! Navidrome and most players look for, and optionally the release's back
+//! cover and booklet pages as well.
+
+use std::io::Cursor;
+use std::path::Path;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+
+use super::tags::is_png;
+use super::{mb_get, DownloaderConfig, MB_USER_AGENT};
+
+const COVER_ART_ARCHIVE: &str = "https://coverartarchive.org";
+
+/// JPEG quality of downscaled embedded covers.
+const EMBED_QUALITY: u8 = 90;
+
+/// Size of the images taken from the Cover Art Archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CoverSize {
+    #[serde(rename = "250")]
+    Small,
+    #[serde(rename = "500")]
+    Medium,
+    #[serde(rename = "1200")]
+    Large,
+    /// As uploaded, which can be several megabytes
+    #[serde(rename = "original")]
+    Original,
+}
+
+impl CoverSize {
+    /// Key in the archive's `thumbnails`, or `None` for the original.
+    fn thumbnail(self) -> Option<&'static str> {
+        match self {
+            CoverSize::Small => Some("250"),
+            CoverSize::Medium => Some("500"),
+            CoverSize::Large => Some("1200"),
+            CoverSize::Original => None,
+        }
+    }
+}
+
+/// An album's front cover and any extra images, as fetched.
+#[derive(Default)]
+pub struct AlbumArt {
+    pub front: Option<Vec<u8>>,
+    /// File name (`back.jpg`, `booklet-01.jpg`, ...) and contents
+    pub extras: Vec<(String, Vec<u8>)>,
+}
+
+impl AlbumArt {
+    /// The front cover to embed in tracks, scaled down if it is too large.
+    pub fn embedded(&self, config: &DownloaderConfig) -> Option<Vec<u8>> {
+        let front = self.front.as_ref()?;
+        if config.embedded_cover_max_px > 0 {
+            if let Some(smaller) = downscale(front, config.embedded_cover_max_px) {
+                return Some(smaller);
+            }
+        }
+        Some(front.clone())
+    }
+
+    /// Write the cover file and extras into `dir`, keeping any that exist.
+    pub fn save(&self, config: &DownloaderConfig, dir: &Path) {
+        if let (Some(front), false) = (&self.front, config.cover_file.is_empty()) {
+            // A PNG cover keeps its format, whatever the configured extension
+            let path = if is_png(front) {
+                dir.join(&config.cover_file).with_extension("png")
+            } else {
+                dir.join(&config.cover_file)
+            };
+            write_new(&path, front);
+        }
+        for (name, data) in &self.extras {
+            write_new(&dir.join(name), data);
+        }
+    }
+}
+
+/// Art for an album: the edition's own, then its release group's, then a
+/// text search for albums without MusicBrainz IDs. Extras come from the same
+/// entry as the front cover, if `with_extras`.
+pub fn album_art(
+    config: &DownloaderConfig,
+    release_id: Option<&str>,
+    release_group_id: Option<&str>,
+    artist: &str,
+    album: &str,
+    with_extras: bool,
+) -> AlbumArt {
+    let mut candidates: Vec<(&str, String)> = Vec::new();
+    if let Some(id) = release_id {
+        candidates.push(("release", id.to_string()));
+    }
+    match release_group_id {
+        Some(rg_id) => candidates.push(("release-group", rg_id.to_string())),
+        None => {
+            candidates.extend(search_release_group(artist, album).map(|id| ("release-group", id)))
+        }
+    }
+
+    for (entity, mbid) in candidates {
+        if let Some(front) = fetch_front(entity, &mbid, config.cover_size) {
+            let extras = if with_extras {
+                fetch_extras(entity, &mbid, config.cover_size)
+            } else {
+                Vec::new()
+            };
+            return AlbumArt {
+                front: Some(front),
+                extras,
+            };
+        }
+    }
+    AlbumArt::default()
+}
+
+fn search_release_group(artist: &str, album: &str) -> Option<String> {
+    let query_str = format!("release:{album} AND artist:{artist}");
+    let encoded = urlencoding::encode(&query_str);
+    let url = format!("release-group/?query={encoded}&fmt=json&limit=1");
+    let data = mb_get(&url).ok()?;
+
+    data["release-groups"].as_array()?.first()?["id"]
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Front cover of `entity` ("release" or "release-group") `mbid`.
+fn fetch_front(entity: &str, mbid: &str, size: CoverSize) -> Option<Vec<u8>> {
+    let url = match size.thumbnail() {
+        Some(px) => format!("{COVER_ART_ARCHIVE}/{entity}/{mbid}/front-{px}"),
+        None => format!("{COVER_ART_ARCHIVE}/{entity}/{mbid}/front"),
+    };
+    fetch_image(&url)
+}
+
+/// Back cover and booklet pages of `entity` `mbid`, named for saving.
+pub fn fetch_extras(entity: &str, mbid: &str, size: CoverSize) -> Vec<(String, Vec<u8>)> {
+    let Some(listing) = get(&format!("{COVER_ART_ARCHIVE}/{entity}/{mbid}"))
+        .and_then(|resp| resp.text().ok())
+        .and_then(|body| serde_json::from_str::<serde_json::Value>(&body).ok())
+    else {
+        return Vec::new();
+    };
+    let images = listing["images"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let mut extras = Vec::new();
+    let mut booklet_pages = 0;
+    for image in images {
+        let types: Vec<&str> = image["types"]
+            .as_array()
+            .map(|t| t.iter().filter_map(|t| t.as_str()).collect())
+            .unwrap_or_default();
+        let name = if types.contains(&"Front") {
+            continue;
+        } else if types.contains(&"Back") {
+            "back".to_string()
+        } else if types.contains(&"Booklet") {
+            booklet_pages += 1;
+            format!("booklet-{booklet_pages:02}")
+        } else {
+            continue;
+        };
+        let url = size
+            .thumbnail()
+            .and_then(|px| image["thumbnails"][px].as_str())
+            .or_else(|| image["image"].as_str());
+        if let Some(data) = url.and_then(fetch_image) {
+            let ext = if is_png(&data) { "png" } else { "jpg" };
+            extras.push((format!("{name}.{ext}"), data));
+        }
+    }
+    extras
+}
+
+fn get(url: &str) -> Option<reqwest::blocking::Response> {
+    let client = reqwest::blocking::Client::builder()
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .build()
+        .ok()?;
+    let resp = client
+        .get(url)
+        .header("User-Agent", MB_USER_AGENT)
+        .send()
+        .ok()?;
+    resp.status().is_success().then_some(resp)
+}
+
+/// A JPEG or PNG at `url`, or `None` for anything else.
+fn fetch_image(url: &str) -> Option<Vec<u8>> {
+    let bytes = get(url)?.bytes().ok()?;
+    if bytes.len() > 3 && (bytes[0..2] == [0xFF, 0xD8] || is_png(&bytes)) {
+        return Some(bytes.to_vec());
+    }
+    None
+}
+
+/// Re-encode `data` as a JPEG no larger than `max_px` on either side, or
+/// `None` if it already fits (or can't be decoded).
+fn downscale(data: &[u8], max_px: u32) -> Option<Vec<u8>> {
+    let image = image::load_from_memory(data).ok()?;
+    if image.width().max(image.height()) <= max_px {
+        return None;
+    }
+    // JPEG has no alpha channel
+    let resized =
+        DynamicImage::ImageRgb8(image.resize(max_px, max_px, FilterType::Lanczos3).to_rgb8());
+    let mut out = Cursor::new(Vec::new());
+    resized
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut out, EMBED_QUALITY))
+        .ok()?;
+    Some(out.into_inner())
+}
+
+/// Write `data` to `path` unless something is already there, via a hidden
+/// temporary file so scanners never see a partial image.
+fn write_new(path: &Path, data: &[u8]) {
+    if path.exists() {
+        return;
+    }
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = path.with_file_name(format!(".{name}.tmp"));
+    if let Err(e) = std::fs::write(&tmp, data).and_then(|_| std::fs::rename(&tmp, path)) {
+        let _ = std::fs::remove_file(&tmp);
+        log::warn!("Failed to write {}: {e}", path.display());
+    }
+}
@@ -0,0 +1,284 @@
+//! Persisted downloader settings.
+//!
+//! Stored as JSON in the app config directory. Missing or unreadable files fall
+//! back to the defaults, and fields added later default individually.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use super::artwork::CoverSize;
+use super::library::DuplicatePolicy;
+use super::lyrics::{self, LyricsMode};
+use super::musicbrainz;
+use super::MbAlbum;
+use super::schedule::TimeWindow;
+use super::template::PathTemplate;
+
+const CONFIG_FILE: &str = "downloader.json";
+
+const MAX_CONCURRENT_TRACKS: usize = 8;
+const MAX_CONCURRENT_ALBUMS: usize = 4;
+
+/// MusicBrainz primary types that `watch_auto_enqueue` may list.
+const RELEASE_TYPES: &[&str] = &["album", "ep", "single", "broadcast", "other"];
+
+/// Default naming scheme, matching the layout used before templates existed.
+pub const DEFAULT_PATH_TEMPLATE: &str = "{albumartist}/{album}/{track:02}-{title}.{ext}";
+
+/// Output format for downloaded tracks.
+///
+/// YouTube serves Opus (WebM) and AAC (M4A) audio. `Opus` and `M4a` prefer a
+/// source in that codec and only remux it; the others transcode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioFormat {
+    Opus,
+    M4a,
+    Mp3,
+    /// Ogg Vorbis
+    Ogg,
+    Flac,
+}
+
+impl AudioFormat {
+    /// File extension yt-dlp gives the converted file.
+    pub fn extension(self) -> &'static str {
+        match self {
+            AudioFormat::Opus => "opus",
+            AudioFormat::M4a => "m4a",
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::Ogg => "ogg",
+            AudioFormat::Flac => "flac",
+        }
+    }
+
+    /// Whether `audio_quality` applies (only to lossy transcodes).
+    pub fn takes_quality(self) -> bool {
+        matches!(self, AudioFormat::Mp3 | AudioFormat::Ogg)
+    }
+
+    /// yt-dlp arguments selecting the source stream and the conversion.
+    pub fn ytdlp_args(self, quality: &str) -> Vec<String> {
+        let (source, codec) = match self {
+            AudioFormat::Opus => (Some("bestaudio[acodec=opus]/bestaudio"), "opus"),
+            AudioFormat::M4a => (Some("bestaudio[ext=m4a]/bestaudio"), "m4a"),
+            AudioFormat::Mp3 => (None, "mp3"),
+            AudioFormat::Ogg => (None, "vorbis"),
+            AudioFormat::Flac => (None, "flac"),
+        };
+        let mut args = Vec::new();
+        if let Some(source) = source {
+            args.extend(["-f".to_string(), source.to_string()]);
+        }
+        args.extend(["-x".to_string(), "--audio-format".to_string(), codec.to_string()]);
+        if self.takes_quality() {
+            args.extend(["--audio-quality".to_string(), quality.to_string()]);
+        }
+        args
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DownloaderConfig {
+    /// Library root that rendered paths are relative to
+    pub music_dir: String,
+    /// Naming template for downloaded tracks (see `template.rs`)
+    pub path_template: String,
+    pub audio_format: AudioFormat,
+    /// For mp3/ogg: a VBR level from 0 (best) to 10, or a bitrate such as `192K`
+    pub audio_quality: String,
+    /// MusicBrainz web service root, overridable for a local mirror or mock
+    pub musicbrainz_url: String,
+    /// How long MusicBrainz responses are reused; 0 disables the cache
+    pub musicbrainz_cache_hours: u64,
+    /// Default handling of tracks the library already has
+    pub duplicate_policy: DuplicatePolicy,
+    /// Only treat a library track as a duplicate if its length matches too
+    pub duplicate_match_duration: bool,
+    /// Kill a yt-dlp run that prints nothing for this long; 0 disables
+    pub stall_timeout_secs: u64,
+    /// Tracks of one album downloaded at the same time
+    pub concurrent_tracks: usize,
+    /// Queue items (albums or songs) run at the same time
+    pub concurrent_albums: usize,
+    /// Delay between starting an album's track workers, to spread out searches
+    pub track_stagger_ms: u64,
+    /// Total download bandwidth in KiB/s, shared by all running downloads; 0 is unlimited
+    pub rate_limit_kib: u64,
+    /// Only start queued items inside this daily window; `None` runs any time
+    pub download_window: Option<TimeWindow>,
+    /// Size of the artwork fetched from the Cover Art Archive
+    pub cover_size: CoverSize,
+    /// Name of the cover image saved in each album folder; empty saves none
+    pub cover_file: String,
+    /// Scale embedded covers down to this many pixels on the longer side; 0 embeds them as fetched
+    pub embedded_cover_max_px: u32,
+    /// Also save the back cover and booklet pages, when the archive has them
+    pub cover_extras: bool,
+    /// Where lyrics fetched for each track are stored
+    pub lyrics: LyricsMode,
+    /// LRCLIB-compatible lyrics service root, overridable for a local mirror or mock
+    pub lyrics_url: String,
+    /// How often followed artists are checked for new releases; 0 only checks on request
+    pub watch_interval_hours: u64,
+    /// Release types (`album`, `ep`, ...) of new releases to queue automatically; empty queues none
+    pub watch_auto_enqueue: Vec<String>,
+    /// Rescan the connected Subsonic server when downloads or imports finish
+    pub auto_scan: bool,
+    /// Wait this long after the last finished download before rescanning, so a burst causes one scan
+    pub scan_delay_secs: u64,
+}
+
+impl Default for DownloaderConfig {
+    fn default() -> Self {
+        Self {
+            music_dir: String::new(),
+            path_template: DEFAULT_PATH_TEMPLATE.into(),
+            audio_format: AudioFormat::Mp3,
+            audio_quality: "0".into(),
+            musicbrainz_url: musicbrainz::DEFAULT_BASE_URL.into(),
+            musicbrainz_cache_hours: 24,
+            duplicate_policy: DuplicatePolicy::Skip,
+            duplicate_match_duration: true,
+            stall_timeout_secs: 180,
+            concurrent_tracks: 3,
+            concurrent_albums: 1,
+            track_stagger_ms: 500,
+            rate_limit_kib: 0,
+            download_window: None,
+            cover_size: CoverSize::Medium,
+            cover_file: "cover.jpg".into(),
+            embedded_cover_max_px: 0,
+            cover_extras: false,
+            lyrics: LyricsMode::Embed,
+            lyrics_url: lyrics::DEFAULT_BASE_URL.into(),
+            watch_interval_hours: 24,
+            watch_auto_enqueue: Vec::new(),
+            auto_scan: true,
+            scan_delay_secs: 30,
+        }
+    }
+}
+
+impl DownloaderConfig {
+    /// Check that the settings are usable before saving them.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.music_dir.trim().is_empty() {
+            return Err("Music directory is not set".into());
+        }
+        if !Path::new(&self.music_dir).is_absolute() {
+            return Err(format!("Music directory must be an absolute path: {}", self.music_dir));
+        }
+        PathTemplate::parse(&self.path_template)?;
+        if self.audio_format.takes_quality() && !is_valid_quality(&self.audio_quality) {
+            return Err(format!(
+                "Invalid audio quality {:?}: use 0-10 or a bitrate like 192K",
+                self.audio_quality
+            ));
+        }
+        if !self.musicbrainz_url.starts_with("http://") && !self.musicbrainz_url.starts_with("https://") {
+            return Err(format!("MusicBrainz URL must be http(s): {}", self.musicbrainz_url));
+        }
+        if !self.lyrics_url.starts_with("http://") && !self.lyrics_url.starts_with("https://") {
+            return Err(format!("Lyrics URL must be http(s): {}", self.lyrics_url));
+        }
+        if !(1..=MAX_CONCURRENT_TRACKS).contains(&self.concurrent_tracks) {
+            return Err(format!("Concurrent tracks must be between 1 and {MAX_CONCURRENT_TRACKS}"));
+        }
+        if !(1..=MAX_CONCURRENT_ALBUMS).contains(&self.concurrent_albums) {
+            return Err(format!("Concurrent albums must be between 1 and {MAX_CONCURRENT_ALBUMS}"));
+        }
+        if let Some(window) = &self.download_window {
+            window.validate()?;
+        }
+        if let Some(t) = self
+            .watch_auto_enqueue
+            .iter()
+            .find(|t| !RELEASE_TYPES.contains(&t.to_ascii_lowercase().as_str()))
+        {
+            return Err(format!("Unknown release type {t:?}: use {}", RELEASE_TYPES.join(", ")));
+        }
+        if self.cover_file.contains(['/', '\\']) || self.cover_file.starts_with('.') {
+            return Err(format!("Cover file must be a plain file name: {}", self.cover_file));
+        }
+        Ok(())
+    }
+
+    /// Each download's share of `rate_limit_kib`, in KiB/s. yt-dlp limits one
+    /// process at a time, so the cap is split over every download that can
+    /// run at once.
+    pub fn rate_limit_per_download(&self) -> Option<u64> {
+        let slots = (self.concurrent_tracks * self.concurrent_albums).max(1) as u64;
+        (self.rate_limit_kib > 0).then(|| (self.rate_limit_kib / slots).max(1))
+    }
+
+    /// Whether a new release from a followed artist should be queued. Only
+    /// plain releases qualify: compilations, live albums and the like are
+    /// just reported.
+    pub fn auto_enqueues(&self, album: &MbAlbum) -> bool {
+        album.secondary_types.is_empty()
+            && self
+                .watch_auto_enqueue
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(&album.release_type))
+    }
+
+    pub fn stall_timeout(&self) -> Duration {
+        Duration::from_secs(self.stall_timeout_secs)
+    }
+
+    pub fn template(&self) -> Result<PathTemplate, String> {
+        PathTemplate::parse(&self.path_template)
+    }
+}
+
+fn is_valid_quality(quality: &str) -> bool {
+    if let Some(kbps) = quality.strip_suffix(['K', 'k']) {
+        return kbps.parse::<u32>().is_ok_and(|k| (8..=512).contains(&k));
+    }
+    quality.parse::<u8>().is_ok_and(|level| level <= 10)
+}
+
+/// Location of the config file.
+pub fn config_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(CONFIG_FILE))
+}
+
+/// Load the saved config, filling in a default music directory on first run.
+pub fn load(app: &AppHandle) -> DownloaderConfig {
+    let mut config = config_path(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|json| match serde_json::from_str::<DownloaderConfig>(&json) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                log::warn!("Ignoring invalid downloader config: {e}");
+                None
+            }
+        })
+        .unwrap_or_default();
+
+    if config.music_dir.is_empty() {
+        if let Ok(dir) = app.path().audio_dir() {
+            config.music_dir = dir.to_string_lossy().to_string();
+        }
+    }
+    if PathTemplate::parse(&config.path_template).is_err() {
+        log::warn!("Invalid path template {:?}, using default", config.path_template);
+        config.path_template = DEFAULT_PATH_TEMPLATE.into();
+    }
+    config
+}
+
+pub fn save(app: &AppHandle, config: &DownloaderConfig) -> Result<(), String> {
+    let path = config_path(app).ok_or("No app config directory")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create config directory: {e}"))?;
+    }
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {e}"))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write config: {e}"))
+}
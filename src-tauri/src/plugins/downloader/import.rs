@@ -0,0 +1,626 @@
+//! Importing music that is already on disk into the library.
+//!
+//! `scan` reads the tags of every audio file under a folder (unpacking any
+//! `.zip` archives into staging first) and groups the files into albums by
+//! their album tags, or by directory for untagged files. `match_album` looks
+//! a group up on MusicBrainz the same way the downloader does and pairs its
+//! files with the release's tracks. Nothing is written until the user has
+//! reviewed the matches: `apply_album` then tags each file, embeds a cover
+//! and copies or moves it to where the naming template puts it.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use lofty::file::{AudioFile, TaggedFileExt};
+use lofty::tag::{Accessor, ItemKey, Tag};
+use serde::{Deserialize, Serialize};
+
+use super::artwork::{self, AlbumArt};
+use super::library::is_audio;
+use super::matching::normalize;
+use super::tags::{self, TrackTags};
+use super::template::{PathTemplate, TrackFields};
+use super::{fetch_release_tracks, mb_get, staging, total_discs, AlbumTrack, DownloaderConfig};
+
+/// Image files used as an album's cover, best first.
+const COVER_NAMES: &[&str] = &["cover", "folder", "front", "album"];
+
+/// An audio file found by `scan`, with what its tags say.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportFile {
+    pub path: String,
+    /// Title tag, or the file name
+    pub title: String,
+    pub artist: String,
+    pub track: Option<usize>,
+    pub disc: Option<usize>,
+    pub duration_secs: Option<f64>,
+    pub has_cover: bool,
+}
+
+/// Files that look like one album.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportAlbum {
+    /// Stable key for the group within one scan
+    pub id: String,
+    /// Folder the files are in (the first one, if they span several)
+    pub dir: String,
+    pub artist: String,
+    pub album: String,
+    pub year: String,
+    pub genre: String,
+    pub files: Vec<ImportFile>,
+    /// Cover image next to the files
+    pub cover_file: Option<String>,
+}
+
+/// What an album will be imported as.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportMatch {
+    /// `None` when nothing was found and the files' own tags are used
+    pub release_id: Option<String>,
+    pub release_group_id: Option<String>,
+    pub artist: String,
+    pub album: String,
+    pub year: String,
+    pub genre: String,
+    pub tracks: Vec<AlbumTrack>,
+    /// For each file of the album, the index of its track; `None` skips it
+    pub assignments: Vec<Option<usize>>,
+}
+
+/// An album the user reviewed, with the match to import it as.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportPlan {
+    pub album: ImportAlbum,
+    pub matched: ImportMatch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Leave the originals where they are
+    Copy,
+    /// Delete each original once its copy is in the library
+    Move,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub imported: usize,
+    /// Files left out, with the reason
+    pub skipped: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+// ────────────────────────────────────────────────────────────────────────────
+// Scanning
+// ────────────────────────────────────────────────────────────────────────────
+
+/// Group the audio files under `source` (a folder or a `.zip`) into albums.
+pub fn scan(source: &Path, config: &DownloaderConfig) -> Result<Vec<ImportAlbum>, String> {
+    let music_dir = Path::new(&config.music_dir);
+    if !config.music_dir.is_empty()
+        && source.starts_with(music_dir)
+        && !source.starts_with(staging::import_dir(music_dir))
+    {
+        return Err("That folder is already inside the library".into());
+    }
+
+    let mut files: Vec<(PathBuf, ImportFile, AlbumTags)> = Vec::new();
+    let mut pending = vec![source.to_path_buf()];
+    while let Some(path) = pending.pop() {
+        if is_zip(&path) {
+            pending.push(unpack(music_dir, source, &path)?);
+        } else if path.is_dir() {
+            let Ok(entries) = std::fs::read_dir(&path) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                if !entry.file_name().to_string_lossy().starts_with('.') {
+                    pending.push(entry.path());
+                }
+            }
+        } else if is_audio(&path) {
+            let (file, album_tags) = read_file(&path);
+            files.push((path, file, album_tags));
+        }
+    }
+    if files.is_empty() && !source.exists() {
+        return Err(format!("{} does not exist", source.display()));
+    }
+
+    // Tagged files group by album artist and album; untagged ones by folder
+    let mut groups: BTreeMap<(String, String, String), ImportAlbum> = BTreeMap::new();
+    for (path, file, album_tags) in files {
+        let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let key = if album_tags.album.is_empty() {
+            (
+                String::new(),
+                String::new(),
+                dir.to_string_lossy().to_string(),
+            )
+        } else {
+            (
+                normalize(&album_tags.artist),
+                normalize(&album_tags.album),
+                String::new(),
+            )
+        };
+        let group = groups.entry(key).or_insert_with(|| ImportAlbum {
+            id: String::new(),
+            dir: dir.to_string_lossy().to_string(),
+            artist: album_tags.artist.clone(),
+            album: album_tags.album.clone(),
+            year: album_tags.year.clone(),
+            genre: album_tags.genre.clone(),
+            files: Vec::new(),
+            cover_file: find_cover(&dir),
+        });
+        if group.year.is_empty() {
+            group.year = album_tags.year;
+        }
+        if group.genre.is_empty() {
+            group.genre = album_tags.genre;
+        }
+        group.files.push(file);
+    }
+
+    let mut albums: Vec<ImportAlbum> = groups.into_values().collect();
+    for (i, album) in albums.iter_mut().enumerate() {
+        album.id = format!("import-{i}");
+        if album.album.is_empty() {
+            // An untagged folder is usually named after the album
+            album.album = Path::new(&album.dir)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+        }
+        if album.artist.is_empty() {
+            album.artist = album
+                .files
+                .iter()
+                .map(|f| f.artist.clone())
+                .find(|a| !a.is_empty())
+                .unwrap_or_default();
+        }
+        album
+            .files
+            .sort_by(|a, b| (a.disc, a.track, &a.path).cmp(&(b.disc, b.track, &b.path)));
+    }
+    Ok(albums)
+}
+
+/// Album-level tags of one file.
+#[derive(Default)]
+struct AlbumTags {
+    /// Album artist, else track artist
+    artist: String,
+    album: String,
+    year: String,
+    genre: String,
+}
+
+fn read_file(path: &Path) -> (ImportFile, AlbumTags) {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut file = ImportFile {
+        path: path.to_string_lossy().to_string(),
+        title: stem,
+        artist: String::new(),
+        track: None,
+        disc: None,
+        duration_secs: None,
+        has_cover: false,
+    };
+    let mut album_tags = AlbumTags::default();
+
+    let Ok(tagged) = lofty::read_from_path(path) else {
+        return (file, album_tags);
+    };
+    file.duration_secs = Some(tagged.properties().duration().as_secs_f64()).filter(|d| *d > 0.0);
+    let Some(tag) = tagged.primary_tag().or_else(|| tagged.first_tag()) else {
+        return (file, album_tags);
+    };
+
+    let text =
+        |s: Option<std::borrow::Cow<str>>| s.map(|s| s.trim().to_string()).unwrap_or_default();
+    if let Some(title) = tag.title().filter(|t| !t.trim().is_empty()) {
+        file.title = title.trim().to_string();
+    }
+    file.artist = text(tag.artist());
+    file.track = tag.track().map(|n| n as usize).filter(|n| *n > 0);
+    file.disc = tag.disk().map(|n| n as usize).filter(|n| *n > 0);
+    file.has_cover = !tag.pictures().is_empty();
+
+    album_tags.artist = tag
+        .get_string(&ItemKey::AlbumArtist)
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| file.artist.clone());
+    album_tags.album = text(tag.album());
+    album_tags.year = tag.year().map(|y| y.to_string()).unwrap_or_default();
+    album_tags.genre = text(tag.genre());
+    (file, album_tags)
+}
+
+fn is_zip(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("zip"))
+}
+
+/// Extract an archive into staging so its files can be read and copied.
+///
+/// The destination mirrors where the archive sits under `source` (or under
+/// staging, for archives inside archives), so `a/disc.zip` and `b/disc.zip`
+/// unpack side by side instead of over each other.
+fn unpack(music_dir: &Path, source: &Path, archive_path: &Path) -> Result<PathBuf, String> {
+    let import_dir = staging::import_dir(music_dir);
+    let relative = archive_path
+        .strip_prefix(&import_dir)
+        .or_else(|_| archive_path.strip_prefix(source.parent().unwrap_or(source)))
+        .unwrap_or(archive_path)
+        .with_extension("");
+    // Never let a root or `..` in the path lead outside staging
+    let dest = relative
+        .components()
+        .filter(|c| matches!(c, std::path::Component::Normal(_)))
+        .fold(import_dir, |dest, c| dest.join(c));
+    let _ = std::fs::remove_dir_all(&dest);
+    std::fs::create_dir_all(&dest)
+        .map_err(|e| format!("Failed to create staging directory: {e}"))?;
+
+    let file = std::fs::File::open(archive_path)
+        .map_err(|e| format!("Failed to open {}: {e}", archive_path.display()))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Not a valid zip archive: {e}"))?;
+    archive
+        .extract(&dest)
+        .map_err(|e| format!("Failed to extract {}: {e}", archive_path.display()))?;
+    Ok(dest)
+}
+
+fn find_cover(dir: &Path) -> Option<String> {
+    let entries = std::fs::read_dir(dir).ok()?;
+    let images: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| matches!(e.to_ascii_lowercase().as_str(), "jpg" | "jpeg" | "png"))
+        })
+        .collect();
+    COVER_NAMES.iter().find_map(|name| {
+        images
+            .iter()
+            .find(|p| {
+                p.file_stem()
+                    .is_some_and(|s| s.to_string_lossy().eq_ignore_ascii_case(name))
+            })
+            .map(|p| p.to_string_lossy().to_string())
+    })
+}
+
+// ────────────────────────────────────────────────────────────────────────────
+// Matching
+// ────────────────────────────────────────────────────────────────────────────
+
+/// Find the MusicBrainz release for `album`, or use `release_id` if the user
+/// picked one. Falls back to the files' own tags when nothing is found.
+pub fn match_album(album: &ImportAlbum, release_id: Option<&str>) -> Result<ImportMatch, String> {
+    let release_id = match release_id {
+        Some(id) => Some(id.to_string()),
+        None => search_release(album)?,
+    };
+    let Some(release_id) = release_id else {
+        return Ok(unmatched(album));
+    };
+
+    let data = mb_get(&format!(
+        "release/{release_id}?inc=artist-credits+release-groups&fmt=json"
+    ))?;
+    let tracks = fetch_release_tracks(&release_id)?;
+    let assignments = assign(&album.files, &tracks);
+    Ok(ImportMatch {
+        release_group_id: data["release-group"]["id"].as_str().map(str::to_string),
+        artist: artist_credit(&data).unwrap_or_else(|| album.artist.clone()),
+        album: data["title"].as_str().unwrap_or(&album.album).to_string(),
+        year: data["date"]
+            .as_str()
+            .and_then(|d| d.get(..4))
+            .map_or_else(|| album.year.clone(), str::to_string),
+        genre: album.genre.clone(),
+        release_id: Some(release_id),
+        tracks,
+        assignments,
+    })
+}
+
+/// The best of the top search results: one with as many tracks as there are
+/// files, else the top hit.
+fn search_release(album: &ImportAlbum) -> Result<Option<String>, String> {
+    if album.album.is_empty() {
+        return Ok(None);
+    }
+    let query_str = format!("release:{} AND artist:{}", album.album, album.artist);
+    let encoded = urlencoding::encode(&query_str);
+    let data = mb_get(&format!("release/?query={encoded}&fmt=json&limit=5"))?;
+    let Some(releases) = data["releases"].as_array() else {
+        return Ok(None);
+    };
+    let release = releases
+        .iter()
+        .find(|r| r["track-count"].as_u64() == Some(album.files.len() as u64))
+        .or_else(|| releases.first());
+    Ok(release.and_then(|r| r["id"].as_str()).map(str::to_string))
+}
+
+fn artist_credit(release: &serde_json::Value) -> Option<String> {
+    let credits = release["artist-credit"].as_array()?;
+    let name: String = credits
+        .iter()
+        .map(|c| {
+            format!(
+                "{}{}",
+                c["name"].as_str().unwrap_or(""),
+                c["joinphrase"].as_str().unwrap_or("")
+            )
+        })
+        .collect();
+    Some(name).filter(|n| !n.is_empty())
+}
+
+/// Import the album as its files are tagged, one track per file.
+fn unmatched(album: &ImportAlbum) -> ImportMatch {
+    let mut disc_tracks: BTreeMap<usize, usize> = BTreeMap::new();
+    for file in &album.files {
+        *disc_tracks.entry(file.disc.unwrap_or(1)).or_default() += 1;
+    }
+    let mut position: BTreeMap<usize, usize> = BTreeMap::new();
+    let tracks = album
+        .files
+        .iter()
+        .map(|file| {
+            let disc = file.disc.unwrap_or(1);
+            let n = position.entry(disc).or_default();
+            *n += 1;
+            AlbumTrack {
+                title: file.title.clone(),
+                disc,
+                track: file.track.unwrap_or(*n),
+                disc_tracks: disc_tracks[&disc],
+                length_ms: file.duration_secs.map(|s| (s * 1000.0) as u64),
+                source_ref: None,
+            }
+        })
+        .collect();
+    ImportMatch {
+        release_id: None,
+        release_group_id: None,
+        artist: album.artist.clone(),
+        album: album.album.clone(),
+        year: album.year.clone(),
+        genre: album.genre.clone(),
+        tracks,
+        assignments: (0..album.files.len()).map(Some).collect(),
+    }
+}
+
+/// Pair files with tracks: by title first, then by disc and track number, and
+/// what is left in order when just as many files as tracks remain.
+fn assign(files: &[ImportFile], tracks: &[AlbumTrack]) -> Vec<Option<usize>> {
+    let mut assignments: Vec<Option<usize>> = vec![None; files.len()];
+    let mut taken = vec![false; tracks.len()];
+
+    for (i, file) in files.iter().enumerate() {
+        let title = normalize(&file.title);
+        let found = tracks
+            .iter()
+            .enumerate()
+            .position(|(t, track)| !taken[t] && normalize(&track.title) == title);
+        if let Some(t) = found {
+            taken[t] = true;
+            assignments[i] = Some(t);
+        }
+    }
+
+    for (i, file) in files.iter().enumerate() {
+        let (None, Some(number)) = (assignments[i], file.track) else {
+            continue;
+        };
+        let disc = file.disc.unwrap_or(1);
+        let found = tracks
+            .iter()
+            .enumerate()
+            .position(|(t, track)| !taken[t] && track.disc == disc && track.track == number);
+        if let Some(t) = found {
+            taken[t] = true;
+            assignments[i] = Some(t);
+        }
+    }
+
+    let left_files: Vec<usize> = (0..files.len())
+        .filter(|i| assignments[*i].is_none())
+        .collect();
+    let left_tracks: Vec<usize> = (0..tracks.len()).filter(|t| !taken[*t]).collect();
+    if left_files.len() == left_tracks.len() {
+        for (i, t) in left_files.into_iter().zip(left_tracks) {
+            assignments[i] = Some(t);
+        }
+    }
+    assignments
+}
+
+// ────────────────────────────────────────────────────────────────────────────
+// Writing
+// ────────────────────────────────────────────────────────────────────────────
+
+/// Tag and file every assigned file of `plan` into the library, with the
+/// album's cover file next to them. Files whose target already exists are
+/// skipped rather than overwritten. `on_file` is called after each file with
+/// the path written, if any.
+pub fn apply_album(
+    config: &DownloaderConfig,
+    template: &PathTemplate,
+    plan: &ImportPlan,
+    mode: ImportMode,
+    report: &mut ImportReport,
+    on_file: &mut dyn FnMut(&ImportFile, Option<&Path>),
+) {
+    let music_dir = Path::new(&config.music_dir);
+    let matched = &plan.matched;
+    let art = album_art(config, plan);
+    let cover = art.embedded(config);
+    let mut album_dirs: Vec<PathBuf> = Vec::new();
+    let total_discs = total_discs(&matched.tracks);
+
+    for (file, assignment) in plan.album.files.iter().zip(&matched.assignments) {
+        let Some(track) = assignment.and_then(|t| matched.tracks.get(t)) else {
+            report
+                .skipped
+                .push(format!("{}: not matched to a track", file.path));
+            on_file(file, None);
+            continue;
+        };
+        let source = Path::new(&file.path);
+        let ext = source
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        let fields = TrackFields {
+            artist: &matched.artist,
+            album_artist: &matched.artist,
+            album: &matched.album,
+            year: &matched.year,
+            genre: &matched.genre,
+            title: &track.title,
+            track: track.track,
+            total_tracks: track.disc_tracks,
+            disc: track.disc,
+            total_discs,
+            ext: &ext,
+        };
+        let final_path = music_dir.join(template.render(&fields));
+        if final_path.exists() {
+            report.skipped.push(format!(
+                "{}: {} already exists",
+                file.path,
+                final_path.display()
+            ));
+            on_file(file, None);
+            continue;
+        }
+
+        let tags = TrackTags {
+            title: &track.title,
+            artist: &matched.artist,
+            album: &matched.album,
+            album_artist: &matched.artist,
+            year: &matched.year,
+            track: track.track,
+            total_tracks: track.disc_tracks,
+            disc: track.disc,
+            total_discs,
+            genre: &matched.genre,
+            release_id: matched.release_id.as_deref(),
+            release_group_id: matched.release_group_id.as_deref(),
+            cover: cover.as_deref(),
+        };
+        match import_file(music_dir, source, &final_path, &tags, mode) {
+            Ok(()) => {
+                report.imported += 1;
+                if let Some(dir) = final_path
+                    .parent()
+                    .filter(|d| !album_dirs.iter().any(|a| a == d))
+                {
+                    album_dirs.push(dir.to_path_buf());
+                }
+                on_file(file, Some(&final_path));
+            }
+            Err(e) => {
+                log::warn!("Failed to import {}: {e}", file.path);
+                report.failed.push(format!("{}: {e}", file.path));
+                on_file(file, None);
+            }
+        }
+    }
+    for dir in &album_dirs {
+        art.save(config, dir);
+    }
+}
+
+fn import_file(
+    music_dir: &Path,
+    source: &Path,
+    final_path: &Path,
+    tags: &TrackTags,
+    mode: ImportMode,
+) -> Result<(), String> {
+    let staged = staging::staged_path(music_dir, final_path);
+    staging::prepare(music_dir, &staged)?;
+    if let Err(e) = std::fs::copy(source, &staged) {
+        staging::discard(music_dir, &staged);
+        return Err(format!("Failed to copy: {e}"));
+    }
+    if let Err(e) = tags::write_tags(&staged, tags) {
+        // Formats we can't tag (WAV, WMA) are still worth having
+        log::warn!("Importing {} without new tags: {e}", source.display());
+    }
+    if let Err(e) = staging::commit(music_dir, &staged, final_path) {
+        staging::discard(music_dir, &staged);
+        return Err(e);
+    }
+    // Files unpacked from an archive are removed with the staging area instead
+    if mode == ImportMode::Move && !source.starts_with(staging::import_dir(music_dir)) {
+        std::fs::remove_file(source)
+            .map_err(|e| format!("Imported, but failed to remove the original: {e}"))?;
+    }
+    Ok(())
+}
+
+/// The album's art: a cover already embedded in its files, then an image
+/// next to them, then the Cover Art Archive.
+fn album_art(config: &DownloaderConfig, plan: &ImportPlan) -> AlbumArt {
+    let matched = &plan.matched;
+    let local = plan
+        .album
+        .files
+        .iter()
+        .filter(|f| f.has_cover)
+        .find_map(|f| embedded_cover(Path::new(&f.path)))
+        .or_else(|| {
+            plan.album
+                .cover_file
+                .as_ref()
+                .and_then(|p| std::fs::read(p).ok())
+        });
+    let Some(front) = local else {
+        return artwork::album_art(
+            config,
+            matched.release_id.as_deref(),
+            matched.release_group_id.as_deref(),
+            &matched.artist,
+            &matched.album,
+            config.cover_extras,
+        );
+    };
+    let extras = match (&matched.release_id, config.cover_extras) {
+        (Some(id), true) => artwork::fetch_extras("release", id, config.cover_size),
+        _ => Vec::new(),
+    };
+    AlbumArt {
+        front: Some(front),
+        extras,
+    }
+}
+
+fn embedded_cover(path: &Path) -> Option<Vec<u8>> {
+    let tagged = lofty::read_from_path(path).ok()?;
+    let tag: &Tag = tagged.primary_tag().or_else(|| tagged.first_tag())?;
+    tag.pictures().first().map(|p| p.data().to_vec())
+}
@@ -0,0 +1,129 @@
+//! On-disk journal of the download queue.
+//!
+//! Every queued item is recorded with its status and finished tracks, and the
+//! file is rewritten (atomically, via a temp file) on each change. Items are
+//! dropped from the journal once they complete or are cancelled, so whatever
+//! is left at startup is unfinished work to offer for resuming.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use super::QueueItem;
+
+const JOURNAL_FILE: &str = "download_queue.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: u64,
+    pub item: QueueItem,
+    pub status: String,
+    /// Total tracks, once the tracklist is known (0 before that)
+    #[serde(default)]
+    pub total_tracks: usize,
+    /// Indices of tracks that finished downloading and tagging
+    #[serde(default)]
+    pub done_tracks: Vec<usize>,
+}
+
+/// Location of the journal file.
+pub fn journal_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join(JOURNAL_FILE))
+}
+
+pub struct Journal {
+    /// `None` when there is no app data directory; the journal then lives in memory only
+    path: Option<PathBuf>,
+    entries: Mutex<Vec<JournalEntry>>,
+}
+
+impl Journal {
+    /// Open the journal at `path`, loading any entries left from a previous run.
+    pub fn open(path: Option<PathBuf>) -> Self {
+        let entries = path
+            .as_deref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|json| match serde_json::from_str(&json) {
+                Ok(entries) => Some(entries),
+                Err(e) => {
+                    log::warn!("Ignoring unreadable download journal: {e}");
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            path,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    pub fn entries(&self) -> Vec<JournalEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    pub fn add(&self, id: u64, item: QueueItem, total_tracks: usize) {
+        self.update(|entries| {
+            entries.push(JournalEntry {
+                id,
+                item,
+                status: "pending".into(),
+                total_tracks,
+                done_tracks: vec![],
+            })
+        });
+    }
+
+    pub fn set_status(&self, id: u64, status: &str) {
+        self.update_entry(id, |e| e.status = status.to_string());
+    }
+
+    pub fn set_total_tracks(&self, id: u64, total_tracks: usize) {
+        self.update_entry(id, |e| e.total_tracks = total_tracks);
+    }
+
+    pub fn track_done(&self, id: u64, track_idx: usize) {
+        self.update_entry(id, |e| {
+            if !e.done_tracks.contains(&track_idx) {
+                e.done_tracks.push(track_idx);
+            }
+        });
+    }
+
+    pub fn remove(&self, id: u64) {
+        self.update(|entries| entries.retain(|e| e.id != id));
+    }
+
+    pub fn retain(&self, keep: impl Fn(&JournalEntry) -> bool) {
+        self.update(|entries| entries.retain(|e| keep(e)));
+    }
+
+    fn update_entry(&self, id: u64, f: impl FnOnce(&mut JournalEntry)) {
+        self.update(|entries| {
+            if let Some(entry) = entries.iter_mut().find(|e| e.id == id) {
+                f(entry);
+            }
+        });
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Vec<JournalEntry>)) {
+        let mut entries = self.entries.lock().unwrap();
+        f(&mut entries);
+        if let Some(path) = &self.path {
+            if let Err(e) = write_atomic(path, &entries) {
+                log::warn!("Failed to write download journal: {e}");
+            }
+        }
+    }
+}
+
+fn write_atomic(path: &Path, entries: &[JournalEntry]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string(entries).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
@@ -0,0 +1,180 @@
+//! Index of tracks already in the library, for duplicate detection.
+//!
+//! Every audio file under the music directory is keyed by normalized artist,
+//! album and title, read from its tags and falling back to the file name for
+//! the title. Album artist and track artist are both indexed, so a track is
+//! found whichever one the library uses. Scanning reads every file's tags, so
+//! the index is cached and only rebuilt when it is old or the root changes.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use lofty::file::{AudioFile, TaggedFileExt};
+use lofty::tag::{Accessor, ItemKey};
+use serde::{Deserialize, Serialize};
+
+use super::matching::normalize;
+
+/// Rebuild the index when it is older than this.
+const MAX_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Durations within this many seconds count as the same recording.
+const DURATION_TOLERANCE: f64 = 5.0;
+
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "flac", "ogg", "opus", "m4a", "aac", "wav", "wma"];
+
+/// What to do with a track the library already has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicatePolicy {
+    /// Count it as done without downloading
+    Skip,
+    /// Download it, then delete the existing copies
+    Replace,
+    /// Download it alongside the existing copies
+    KeepBoth,
+}
+
+#[derive(Debug, Clone)]
+pub struct OwnedFile {
+    pub path: PathBuf,
+    pub duration_secs: Option<f64>,
+}
+
+type Key = (String, String, String);
+
+pub struct LibraryIndex {
+    root: PathBuf,
+    built: Instant,
+    files: HashMap<Key, Vec<OwnedFile>>,
+}
+
+impl LibraryIndex {
+    /// Read the tags of every audio file under `root`.
+    pub fn scan(root: &Path) -> Self {
+        let mut index = Self {
+            root: root.to_path_buf(),
+            built: Instant::now(),
+            files: HashMap::new(),
+        };
+        let mut dirs = vec![root.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                // Skips the downloader's staging area along with other hidden folders
+                if entry.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if is_audio(&path) {
+                    index.add_file(&path);
+                }
+            }
+        }
+        log::info!(
+            "Indexed {} tracks under {} in {:.1}s",
+            index.files.values().map(Vec::len).sum::<usize>(),
+            root.display(),
+            index.built.elapsed().as_secs_f64()
+        );
+        index
+    }
+
+    /// Whether the index still describes `root` closely enough to reuse.
+    pub fn is_fresh(&self, root: &Path) -> bool {
+        self.root == root && self.built.elapsed() < MAX_AGE
+    }
+
+    /// Index a file that was just written.
+    pub fn add_file(&mut self, path: &Path) {
+        let Ok(file) = lofty::read_from_path(path) else {
+            return;
+        };
+        let duration_secs = Some(file.properties().duration().as_secs_f64()).filter(|d| *d > 0.0);
+        let tag = file.primary_tag().or_else(|| file.first_tag());
+
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let title = tag
+            .and_then(|t| t.title().map(|s| s.to_string()))
+            .unwrap_or(stem);
+        let album = tag.and_then(|t| t.album().map(|s| s.to_string())).unwrap_or_default();
+        let mut artists: Vec<String> = Vec::new();
+        if let Some(tag) = tag {
+            artists.extend(tag.artist().map(|s| s.to_string()));
+            artists.extend(tag.get_string(&ItemKey::AlbumArtist).map(|s| s.to_string()));
+        }
+
+        let owned = OwnedFile {
+            path: path.to_path_buf(),
+            duration_secs,
+        };
+        let mut keys: Vec<Key> = artists
+            .iter()
+            .map(|artist| (normalize(artist), normalize(&album), normalize(&title)))
+            .collect();
+        keys.dedup();
+        for key in keys {
+            self.files.entry(key).or_default().push(owned.clone());
+        }
+    }
+
+    /// Library files that look like the same track, other than `exclude`.
+    ///
+    /// With `duration_secs` set, files whose length is known and differs by
+    /// more than a few seconds (another version) are not counted.
+    pub fn find(
+        &self,
+        artist: &str,
+        album: &str,
+        title: &str,
+        duration_secs: Option<f64>,
+        exclude: &Path,
+    ) -> Vec<OwnedFile> {
+        let key = (normalize(artist), normalize(album), normalize(title));
+        self.files
+            .get(&key)
+            .into_iter()
+            .flatten()
+            .filter(|f| f.path != exclude && f.path.exists())
+            .filter(|f| match (f.duration_secs, duration_secs) {
+                (Some(have), Some(want)) => (have - want).abs() <= DURATION_TOLERANCE,
+                _ => true,
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Whether the library has any track of `album` by `artist`.
+    pub fn has_album(&self, artist: &str, album: &str) -> bool {
+        let (artist, album) = (normalize(artist), normalize(album));
+        self.files.iter().any(|((a, b, _), files)| {
+            *a == artist && *b == album && files.iter().any(|f| f.path.exists())
+        })
+    }
+
+    /// Forget `path`, e.g. after replacing it.
+    pub fn remove_file(&mut self, path: &Path) {
+        for files in self.files.values_mut() {
+            files.retain(|f| f.path != path);
+        }
+    }
+}
+
+pub fn is_audio(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| AUDIO_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// The file at `path` in whichever audio format it was saved, if any.
+pub fn existing_audio(path: &Path) -> Option<PathBuf> {
+    AUDIO_EXTENSIONS
+        .iter()
+        .map(|ext| path.with_extension(ext))
+        .find(|p| p.exists())
+}
@@ -0,0 +1,272 @@
+//! Lyrics from LRCLIB, stored with downloaded tracks.
+//!
+//! Each finished track is looked up by artist, title, album and its actual
+//! length, which is what keeps synced lyrics in time with this particular
+//! upload. If the exact lookup finds nothing, a search picks the result
+//! closest in length. Lyrics are embedded (ID3 `USLT` plus `SYLT` for synced
+//! ones in MP3, a `LYRICS` field elsewhere) and/or written as a `.lrc` file
+//! next to the track, as configured. A failed lookup never fails a download.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use id3::TagLike;
+use lofty::config::WriteOptions;
+use lofty::file::{AudioFile, TaggedFileExt};
+use lofty::tag::{ItemKey, TagExt};
+use serde::{Deserialize, Serialize};
+
+use super::tags::TrackTags;
+use super::{DownloaderConfig, MB_USER_AGENT};
+
+pub const DEFAULT_BASE_URL: &str = "https://lrclib.net";
+
+/// Results further than this from the track's length are other versions.
+const DURATION_TOLERANCE: f64 = 3.0;
+
+/// Where fetched lyrics are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LyricsMode {
+    /// Don't fetch lyrics
+    Off,
+    /// In the track's tags
+    Embed,
+    /// In a `.lrc` file next to the track
+    Sidecar,
+    Both,
+}
+
+impl LyricsMode {
+    fn embeds(self) -> bool {
+        matches!(self, LyricsMode::Embed | LyricsMode::Both)
+    }
+
+    fn writes_sidecar(self) -> bool {
+        matches!(self, LyricsMode::Sidecar | LyricsMode::Both)
+    }
+}
+
+/// One LRCLIB record.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Record {
+    #[serde(default)]
+    duration: Option<f64>,
+    #[serde(default)]
+    instrumental: bool,
+    plain_lyrics: Option<String>,
+    /// LRC text with `[mm:ss.xx]` timestamps
+    synced_lyrics: Option<String>,
+}
+
+impl Record {
+    fn has_lyrics(&self) -> bool {
+        !self.instrumental && (present(&self.synced_lyrics) || present(&self.plain_lyrics))
+    }
+}
+
+fn present(text: &Option<String>) -> bool {
+    text.as_deref().is_some_and(|t| !t.trim().is_empty())
+}
+
+/// Look up lyrics for the staged track at `staged` and store them as
+/// configured. Returns the staged `.lrc` file to move into the library along
+/// with the track, if one was written.
+pub fn add(config: &DownloaderConfig, staged: &Path, tags: &TrackTags) -> Option<PathBuf> {
+    if config.lyrics == LyricsMode::Off {
+        return None;
+    }
+    let duration = lofty::read_from_path(staged)
+        .ok()
+        .map(|f| f.properties().duration().as_secs_f64())
+        .filter(|d| *d > 0.0);
+    let record = match lookup(config, tags, duration) {
+        Ok(Some(record)) => record,
+        Ok(None) => {
+            log::info!("No lyrics for \"{} - {}\"", tags.artist, tags.title);
+            return None;
+        }
+        Err(e) => {
+            log::warn!(
+                "Lyrics lookup for \"{} - {}\" failed: {e}",
+                tags.artist,
+                tags.title
+            );
+            return None;
+        }
+    };
+
+    if config.lyrics.embeds() {
+        if let Err(e) = embed(staged, &record) {
+            log::warn!("Failed to embed lyrics in {}: {e}", staged.display());
+        }
+    }
+    if !config.lyrics.writes_sidecar() {
+        return None;
+    }
+    // Plain lyrics in an .lrc are shown unsynced by players that read them
+    let text = record
+        .synced_lyrics
+        .as_ref()
+        .filter(|t| !t.trim().is_empty());
+    let text = text.or(record.plain_lyrics.as_ref())?;
+    let lrc = staged.with_extension("lrc");
+    match std::fs::write(&lrc, text) {
+        Ok(()) => Some(lrc),
+        Err(e) => {
+            log::warn!("Failed to write {}: {e}", lrc.display());
+            None
+        }
+    }
+}
+
+/// The exact record for the track, else the search result closest in length.
+fn lookup(
+    config: &DownloaderConfig,
+    tags: &TrackTags,
+    duration: Option<f64>,
+) -> Result<Option<Record>, String> {
+    let base = config.lyrics_url.trim_end_matches('/');
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(MB_USER_AGENT)
+        .timeout(Duration::from_secs(15))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    if let Some(duration) = duration {
+        let query = [
+            ("artist_name", tags.artist.to_string()),
+            ("track_name", tags.title.to_string()),
+            ("album_name", tags.album.to_string()),
+            ("duration", format!("{}", duration.round() as u64)),
+        ];
+        let resp = client
+            .get(format!("{base}/api/get"))
+            .query(&query)
+            .send()
+            .map_err(|e| e.to_string())?;
+        if resp.status().is_success() {
+            let record: Record = parse(resp)?;
+            if record.has_lyrics() {
+                return Ok(Some(record));
+            }
+        } else if resp.status() != reqwest::StatusCode::NOT_FOUND {
+            return Err(format!("LRCLIB returned {}", resp.status()));
+        }
+    }
+
+    let query = [("artist_name", tags.artist), ("track_name", tags.title)];
+    let resp = client
+        .get(format!("{base}/api/search"))
+        .query(&query)
+        .send()
+        .map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("LRCLIB returned {}", resp.status()));
+    }
+    let results: Vec<Record> = parse(resp)?;
+    let distance = |r: &Record| match (r.duration, duration) {
+        (Some(have), Some(want)) => (have - want).abs(),
+        _ => 0.0,
+    };
+    Ok(results
+        .into_iter()
+        .filter(|r| r.has_lyrics() && distance(r) <= DURATION_TOLERANCE)
+        // Synced first, then the closest length
+        .min_by(|a, b| {
+            (!present(&a.synced_lyrics))
+                .cmp(&!present(&b.synced_lyrics))
+                .then(distance(a).total_cmp(&distance(b)))
+        }))
+}
+
+fn parse<T: serde::de::DeserializeOwned>(resp: reqwest::blocking::Response) -> Result<T, String> {
+    let body = resp.text().map_err(|e| e.to_string())?;
+    serde_json::from_str(&body).map_err(|e| format!("Failed to parse LRCLIB response: {e}"))
+}
+
+/// Add the lyrics to the tags `write_tags` already wrote.
+fn embed(path: &Path, record: &Record) -> Result<(), String> {
+    let synced = record
+        .synced_lyrics
+        .as_deref()
+        .filter(|t| !t.trim().is_empty())
+        .map(parse_lrc)
+        .filter(|lines| !lines.is_empty());
+    let plain = match (&record.plain_lyrics, &synced) {
+        (Some(text), _) if !text.trim().is_empty() => text.clone(),
+        (_, Some(lines)) => lines
+            .iter()
+            .map(|(_, line)| line.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => return Ok(()),
+    };
+
+    let is_mp3 = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("mp3"));
+    if is_mp3 {
+        let mut tag = id3::Tag::read_from_path(path).unwrap_or_default();
+        tag.remove_all_lyrics();
+        tag.remove_all_synchronised_lyrics();
+        tag.add_frame(id3::frame::Lyrics {
+            lang: "XXX".to_string(),
+            description: String::new(),
+            text: plain,
+        });
+        if let Some(lines) = synced {
+            tag.add_frame(id3::frame::SynchronisedLyrics {
+                lang: "XXX".to_string(),
+                timestamp_format: id3::frame::TimestampFormat::Ms,
+                content_type: id3::frame::SynchronisedLyricsType::Lyrics,
+                description: String::new(),
+                content: lines,
+            });
+        }
+        return tag
+            .write_to_path(path, id3::Version::Id3v24)
+            .map_err(|e| format!("Failed to write ID3 tag: {e}"));
+    }
+
+    let mut file = lofty::read_from_path(path).map_err(|e| format!("Failed to read file: {e}"))?;
+    let tag = file.primary_tag_mut().ok_or("File has no tags")?;
+    // Players that sync lyrics read LRC timestamps from this field
+    let text = record
+        .synced_lyrics
+        .clone()
+        .filter(|_| synced.is_some())
+        .unwrap_or(plain);
+    tag.insert_text(ItemKey::Lyrics, text);
+    tag.save_to_path(path, WriteOptions::default())
+        .map_err(|e| format!("Failed to write tags: {e}"))
+}
+
+/// `[mm:ss.xx]` lines as millisecond timestamps; a line with several
+/// timestamps is repeated for each.
+fn parse_lrc(text: &str) -> Vec<(u32, String)> {
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        let mut rest = line.trim();
+        let mut times = Vec::new();
+        while let Some(stamp) = rest.strip_prefix('[').and_then(|r| r.split_once(']')) {
+            let Some(ms) = parse_timestamp(stamp.0) else {
+                // Metadata such as [ar:Artist]
+                break;
+            };
+            times.push(ms);
+            rest = stamp.1.trim_start();
+        }
+        lines.extend(times.into_iter().map(|ms| (ms, rest.to_string())));
+    }
+    lines.sort_by_key(|(ms, _)| *ms);
+    lines
+}
+
+fn parse_timestamp(stamp: &str) -> Option<u32> {
+    let (minutes, seconds) = stamp.split_once(':')?;
+    let minutes: u32 = minutes.parse().ok()?;
+    let seconds: f64 = seconds.replace(':', ".").parse().ok()?;
+    Some(minutes * 60_000 + (seconds * 1000.0).round() as u32)
+}
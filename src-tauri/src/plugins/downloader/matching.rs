@@ -0,0 +1,148 @@
+//! Picking the YouTube upload that best matches a track.
+//!
+//! Search results are scored rather than taken in order. The strongest signal
+//! is the duration against the MusicBrainz recording length. Auto-generated
+//! "Artist - Topic" uploads and the artist's own channel come next, since they
+//! carry the studio version. Titles that mark a different version (live, cover,
+//! remix, sped up, ...) are penalized unless the track title says the same.
+
+use serde::{Deserialize, Serialize};
+
+/// Search results fetched per track.
+pub const CANDIDATES: usize = 8;
+
+/// Words marking a version other than the studio recording.
+const VERSION_KEYWORDS: &[&str] = &[
+    "live",
+    "cover",
+    "remix",
+    "sped up",
+    "slowed",
+    "reverb",
+    "nightcore",
+    "karaoke",
+    "instrumental",
+    "acoustic",
+    "8d",
+    "loop",
+    "hour",
+    "hours",
+    "reaction",
+    "tutorial",
+];
+
+/// One search result.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub id: String,
+    pub title: String,
+    pub channel: String,
+    /// Seconds, when yt-dlp reports it
+    pub duration: Option<f64>,
+}
+
+/// The upload chosen for a track, reported in progress events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackMatch {
+    /// What the source fetches: a video ID, URL or file name
+    pub id: String,
+    pub title: String,
+    pub channel: String,
+    pub duration: Option<f64>,
+    pub score: f64,
+}
+
+/// Lowercase, with punctuation turned into single spaces.
+pub fn normalize(s: &str) -> String {
+    s.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn contains_words(haystack: &str, needle: &str) -> bool {
+    !needle.is_empty() && format!(" {haystack} ").contains(&format!(" {needle} "))
+}
+
+/// Score `candidate` for `track` by `artist`; higher is better.
+///
+/// `rank` is the result's position in the search, used only to break near-ties
+/// in favour of YouTube's own relevance order.
+pub fn score(
+    candidate: &Candidate,
+    rank: usize,
+    artist: &str,
+    track: &str,
+    expected_secs: Option<f64>,
+) -> f64 {
+    let title = normalize(&candidate.title);
+    let channel = normalize(&candidate.channel);
+    let artist = normalize(artist);
+    let track = normalize(track);
+    let mut score = 0.0;
+
+    match (candidate.duration, expected_secs) {
+        (Some(actual), Some(expected)) => {
+            // Full marks within a few seconds, then 2 points per second off
+            let off = (actual - expected).abs();
+            score += if off <= 3.0 { 40.0 } else { (40.0 - off * 2.0).max(-100.0) };
+        }
+        // Without a reference, only rule out loops and full-album uploads
+        (Some(actual), None) if actual > 15.0 * 60.0 => score -= 30.0,
+        _ => {}
+    }
+
+    if candidate.channel.ends_with(" - Topic") {
+        score += 30.0;
+    } else if channel == artist
+        || channel == format!("{artist} official")
+        || channel.replace(' ', "") == format!("{}vevo", artist.replace(' ', ""))
+    {
+        score += 20.0;
+    }
+
+    if contains_words(&title, &track) {
+        score += 15.0;
+    }
+    if contains_words(&title, &artist) {
+        score += 5.0;
+    }
+    if title.contains("official audio") {
+        score += 10.0;
+    } else if title.contains("official video") || title.contains("music video") {
+        // Videos often add intros and skits
+        score -= 5.0;
+    }
+
+    for keyword in VERSION_KEYWORDS {
+        if contains_words(&title, keyword) && !contains_words(&track, keyword) {
+            score -= 40.0;
+        }
+    }
+
+    score + (CANDIDATES.saturating_sub(rank)) as f64 * 0.5
+}
+
+/// The best-scoring candidate, if any.
+pub fn best(
+    candidates: &[Candidate],
+    artist: &str,
+    track: &str,
+    expected_secs: Option<f64>,
+) -> Option<TrackMatch> {
+    candidates
+        .iter()
+        .enumerate()
+        .map(|(rank, c)| (c, score(c, rank, artist, track, expected_secs)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(c, score)| TrackMatch {
+            id: c.id.clone(),
+            title: c.title.clone(),
+            channel: c.channel.clone(),
+            duration: c.duration,
+            score,
+        })
+}
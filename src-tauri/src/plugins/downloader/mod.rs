@@ -0,0 +1,2546 @@
+mod artwork;
+mod config;
+mod import;
+mod journal;
+mod library;
+mod lyrics;
+mod matching;
+mod musicbrainz;
+mod process;
+mod progress;
+mod rescan;
+mod schedule;
+mod sources;
+mod staging;
+mod tags;
+mod template;
+mod watchlist;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+pub use config::DownloaderConfig;
+use import::{ImportAlbum, ImportMatch, ImportMode, ImportPlan, ImportReport};
+use journal::Journal;
+use library::{DuplicatePolicy, LibraryIndex};
+use lyrics::LyricsMode;
+use matching::TrackMatch;
+use progress::TrackProgress;
+use rescan::{Rescanner, ScanServer};
+use sources::{DownloadSource, FetchContext, Source, TrackQuery};
+use tags::TrackTags;
+use template::{PathTemplate, TrackFields};
+use watchlist::{WatchedArtist, Watchlist};
+
+// ────────────────────────────────────────────────────────────────────────────
+// Types
+// ────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MbArtist {
+    pub id: String,
+    pub name: String,
+    pub disambiguation: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MbAlbum {
+    pub id: String,
+    pub title: String,
+    pub year: String,
+    #[serde(rename = "type")]
+    pub release_type: String,
+    pub secondary_types: Vec<String>,
+    /// First release date, `YYYY`, `YYYY-MM` or `YYYY-MM-DD`
+    #[serde(default)]
+    pub release_date: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlbumRequest {
+    pub artist: String,
+    pub album: String,
+    pub year: String,
+    pub genre: String,
+    pub tracks: Option<Vec<AlbumTrack>>,
+    /// MusicBrainz release group the album was picked from
+    #[serde(default)]
+    pub release_group_id: Option<String>,
+    /// Specific edition (MusicBrainz release) to use for tracks and cover
+    #[serde(default)]
+    pub release_id: Option<String>,
+    /// Overrides the configured duplicate policy for this album
+    #[serde(default)]
+    pub duplicate_policy: Option<DuplicatePolicy>,
+    /// Download only these tracklist indices (retrying failed tracks)
+    #[serde(default)]
+    pub only_tracks: Option<Vec<usize>>,
+    /// Backend the tracks come from
+    #[serde(default)]
+    pub source: Source,
+}
+
+/// One edition of a release group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MbRelease {
+    pub id: String,
+    pub title: String,
+    pub disambiguation: String,
+    pub status: String,
+    pub date: String,
+    pub country: String,
+    /// Media formats, e.g. "CD" or "CD + DVD"
+    pub format: String,
+    pub disc_count: usize,
+    pub track_count: usize,
+}
+
+/// One entry of an album tracklist, numbered within its disc.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlbumTrack {
+    pub title: String,
+    pub disc: usize,
+    /// Position on the disc, from 1
+    pub track: usize,
+    /// Number of tracks on this disc
+    pub disc_tracks: usize,
+    /// Recording length from MusicBrainz, used to pick the right upload
+    #[serde(default)]
+    pub length_ms: Option<u64>,
+    /// What to fetch for this track (a URL, or a file of a local source)
+    /// instead of searching the album's source
+    #[serde(default)]
+    pub source_ref: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SongRequest {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub year: String,
+    pub genre: String,
+    pub track_num: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YtSearchResult {
+    pub id: String,
+    pub title: String,
+    pub duration: String,
+    pub channel: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DownloadProgress {
+    pub album_index: usize,
+    pub total_albums: usize,
+    pub artist: String,
+    pub album: String,
+    pub track_index: usize,
+    pub total_tracks: usize,
+    pub track_name: String,
+    pub status: String,
+    pub error: Option<String>,
+    /// The YouTube upload picked for the track, once searched
+    #[serde(default)]
+    pub matched: Option<TrackMatch>,
+    /// Live byte progress while downloading
+    #[serde(default)]
+    pub progress: Option<TrackProgress>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadState {
+    pub is_active: bool,
+    pub albums: Vec<AlbumDownloadState>,
+    /// Estimated seconds until the whole queue is done
+    #[serde(default)]
+    pub eta_secs: Option<u64>,
+    /// When the download window opens (`HH:MM`), while queued items wait for it
+    #[serde(default)]
+    pub waiting_for_window: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveTrack {
+    pub track_index: usize,
+    pub track_name: String,
+    pub status: String,
+    #[serde(default)]
+    pub matched: Option<TrackMatch>,
+    #[serde(default)]
+    pub progress: Option<TrackProgress>,
+}
+
+/// Outcome of one track of an album.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackState {
+    pub track_index: usize,
+    pub title: String,
+    pub disc: usize,
+    pub track: usize,
+    /// `pending`, `done`, `owned` or `error`
+    pub status: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscProgress {
+    pub disc: usize,
+    pub completed_tracks: usize,
+    pub total_tracks: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlbumDownloadState {
+    /// Stable ID of the queue item, kept across restarts
+    pub id: u64,
+    pub artist: String,
+    pub album: String,
+    pub status: String,
+    pub completed_tracks: usize,
+    pub total_tracks: usize,
+    pub error: Option<String>,
+    pub active_tracks: Vec<ActiveTrack>,
+    /// Per-disc progress, once the tracklist is known
+    #[serde(default)]
+    pub discs: Vec<DiscProgress>,
+    /// Every track's status, once the tracklist is known
+    #[serde(default)]
+    pub tracks: Vec<TrackState>,
+    /// Bytes downloaded so far, finished tracks included
+    #[serde(default)]
+    pub downloaded_bytes: u64,
+    /// Estimated album size, extrapolated from the tracks sized so far
+    #[serde(default)]
+    pub total_bytes: u64,
+    /// Combined speed of the active tracks, in bytes per second
+    #[serde(default)]
+    pub speed: f64,
+    #[serde(default)]
+    pub eta_secs: Option<u64>,
+    /// Size and count of the tracks downloaded so far (skipped ones excluded)
+    #[serde(skip)]
+    finished_bytes: u64,
+    #[serde(skip)]
+    finished_sized: usize,
+    /// The request, kept for retrying
+    #[serde(skip)]
+    queue_item: Option<QueueItem>,
+}
+
+/// Represents a queued work item for the download worker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum QueueItem {
+    Album(AlbumRequest),
+    Song {
+        song: SongRequest,
+        /// What to fetch: a YouTube video ID, a URL or a file, depending on `source`
+        video_id: String,
+        #[serde(default)]
+        source: Source,
+    },
+}
+
+impl QueueItem {
+    /// Initial queue display state for this item.
+    fn album_state(&self, id: u64, status: &str) -> AlbumDownloadState {
+        let (artist, album, total_tracks) = match self {
+            QueueItem::Album(req) => (req.artist.clone(), req.album.clone(), 0),
+            QueueItem::Song { song, .. } => {
+                (song.artist.clone(), format!("{} (Single)", song.title), 1)
+            }
+        };
+        AlbumDownloadState {
+            id,
+            artist,
+            album,
+            status: status.into(),
+            completed_tracks: 0,
+            total_tracks,
+            error: None,
+            active_tracks: vec![],
+            discs: vec![],
+            tracks: vec![],
+            downloaded_bytes: 0,
+            total_bytes: 0,
+            speed: 0.0,
+            eta_secs: None,
+            finished_bytes: 0,
+            finished_sized: 0,
+            queue_item: Some(self.clone()),
+        }
+    }
+}
+
+pub struct DownloaderStateInner {
+    pub state: Mutex<DownloadState>,
+    pub cancel: Mutex<bool>,
+    /// Persisted settings; downloads snapshot this when they start.
+    pub config: Mutex<DownloaderConfig>,
+    /// Number of queue worker threads currently running.
+    worker_running: Mutex<usize>,
+    /// Pending items that haven't been picked up by the worker yet.
+    pending_queue: Mutex<Vec<(u64, QueueItem)>>,
+    /// Persisted copy of the queue, for resuming after a restart.
+    journal: Journal,
+    next_id: AtomicU64,
+    /// Tracks already under the music directory, built on first use
+    library: Mutex<Option<LibraryIndex>>,
+    /// Running items asked to stop, with the status to leave them in
+    /// (`cancelled` or `paused`)
+    stop_requests: Mutex<HashMap<u64, &'static str>>,
+    /// Followed artists
+    watchlist: Watchlist,
+    /// Batched library rescans on the Subsonic server
+    rescanner: Arc<Rescanner>,
+}
+
+#[derive(Clone)]
+pub struct DownloaderState(pub Arc<DownloaderStateInner>);
+
+impl DownloaderState {
+    /// Create the downloader state with the saved config.
+    ///
+    /// Items left unfinished by a previous run are restored as `paused`;
+    /// `downloader_resume` puts them back in the queue.
+    pub fn load(app: &AppHandle) -> Self {
+        let journal = Journal::open(journal::journal_path(app));
+        let watchlist = Watchlist::open(watchlist::watchlist_path(app));
+        let config = config::load(app);
+        musicbrainz::init(app, &config);
+        staging::sweep(Path::new(&config.music_dir));
+
+        let mut albums = Vec::new();
+        for entry in journal.entries() {
+            let mut album = entry.item.album_state(entry.id, "paused");
+            album.completed_tracks = entry.done_tracks.len();
+            album.total_tracks = album.total_tracks.max(entry.total_tracks);
+            albums.push(album);
+            journal.set_status(entry.id, "paused");
+        }
+        let next_id = albums.iter().map(|a| a.id).max().unwrap_or(0) + 1;
+
+        let inner = Arc::new(DownloaderStateInner {
+            state: Mutex::new(DownloadState {
+                is_active: false,
+                albums,
+                eta_secs: None,
+                waiting_for_window: None,
+            }),
+            cancel: Mutex::new(false),
+            config: Mutex::new(config),
+            worker_running: Mutex::new(0),
+            pending_queue: Mutex::new(vec![]),
+            journal,
+            next_id: AtomicU64::new(next_id),
+            library: Mutex::new(None),
+            stop_requests: Mutex::new(HashMap::new()),
+            watchlist,
+            rescanner: Arc::default(),
+        });
+
+        let app = app.clone();
+        let scheduler_state = Arc::clone(&inner);
+        std::thread::spawn(move || run_watch_scheduler(app, scheduler_state));
+        Self(inner)
+    }
+}
+
+/// Minimum gap between progress events for one track.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+const MB_USER_AGENT: &str = "LuminaMusicPlayer/1.0 (https://github.com/lumina)";
+
+// ────────────────────────────────────────────────────────────────────────────
+// yt-dlp / ffmpeg path resolution
+// ────────────────────────────────────────────────────────────────────────────
+
+fn find_binary(name: &str) -> String {
+    // Try PATH first
+    if let Ok(output) = Command::new("which").arg(name).output() {
+        if output.status.success() {
+            let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !path.is_empty() {
+                return path;
+            }
+        }
+    }
+
+    // Search common nix store locations
+    if let Ok(output) = Command::new("bash")
+        .args(["-c", &format!(
+            "find /nix/store -maxdepth 3 -name '{}' -type f 2>/dev/null | head -1", name
+        )])
+        .output()
+    {
+        if output.status.success() {
+            let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !path.is_empty() {
+                return path;
+            }
+        }
+    }
+
+    // Fallback: hope it's on PATH at runtime
+    name.to_string()
+}
+
+fn yt_dlp_path() -> String {
+    use std::sync::OnceLock;
+    static PATH: OnceLock<String> = OnceLock::new();
+    PATH.get_or_init(|| find_binary("yt-dlp")).clone()
+}
+
+fn ffmpeg_dir() -> Option<String> {
+    use std::sync::OnceLock;
+    static DIR: OnceLock<Option<String>> = OnceLock::new();
+    DIR.get_or_init(|| {
+        let path = find_binary("ffmpeg");
+        if path == "ffmpeg" {
+            None
+        } else {
+            Path::new(&path).parent().map(|p| p.to_string_lossy().to_string())
+        }
+    })
+    .clone()
+}
+
+// ────────────────────────────────────────────────────────────────────────────
+// MusicBrainz helpers
+// ────────────────────────────────────────────────────────────────────────────
+
+/// GET a path under the MusicBrainz base URL (rate limited and cached).
+fn mb_get(path: &str) -> Result<serde_json::Value, String> {
+    musicbrainz::client().get(path)
+}
+
+// ────────────────────────────────────────────────────────────────────────────
+// Workers: each processes queue items sequentially
+// ────────────────────────────────────────────────────────────────────────────
+
+/// Start queue workers until `concurrent_albums` are running. Running
+/// workers pick up newly queued items themselves.
+fn ensure_worker(app: &AppHandle, inner: &Arc<DownloaderStateInner>) {
+    let mut running = inner.worker_running.lock().unwrap();
+    if *running == 0 {
+        // Reset cancel
+        *inner.cancel.lock().unwrap() = false;
+    }
+    let wanted = inner.config.lock().unwrap().concurrent_albums.max(1);
+    while *running < wanted {
+        *running += 1;
+        let state_arc = inner.clone();
+        let app = app.clone();
+        std::thread::spawn(move || run_worker(app, state_arc));
+    }
+}
+
+/// Process queue items one at a time until the queue is empty. The last
+/// worker to finish reports the queue as complete.
+fn run_worker(app: AppHandle, state_arc: Arc<DownloaderStateInner>) {
+    let last_worker = loop {
+        wait_for_window(&app, &state_arc);
+
+        // Grab the next item from the pending queue
+        let (id, item) = {
+            let mut running = state_arc.worker_running.lock().unwrap();
+            let mut queue = state_arc.pending_queue.lock().unwrap();
+            if queue.is_empty() {
+                // Nothing left — shut down worker. Decided under the worker
+                // lock so an item queued meanwhile starts a new worker.
+                *running -= 1;
+                break *running == 0;
+            }
+            queue.remove(0)
+        };
+
+        // Check cancel
+        if *state_arc.cancel.lock().unwrap() {
+            // Drain remaining items and mark them cancelled
+            let remaining = {
+                let mut queue = state_arc.pending_queue.lock().unwrap();
+                let items: Vec<_> = queue.drain(..).collect();
+                items
+            };
+            // Mark current + remaining as error in state
+            {
+                let mut s = state_arc.state.lock().unwrap();
+                for album_state in s.albums.iter_mut() {
+                    if album_state.status == "pending" || album_state.status == "downloading" {
+                        album_state.status = "cancelled".into();
+                        state_arc.journal.remove(album_state.id);
+                    }
+                }
+                s.is_active = false;
+            }
+            let _ = remaining; // just drop them
+            let _ = app.emit("download-cancelled", ());
+            // The queue is empty now, so the next pass shuts the worker down
+            continue;
+        }
+
+        // Mark downloading, unless the item was removed or already processed.
+        // Items are looked up by id from here on: clearing finished ones
+        // moves the rest around in `albums`.
+        {
+            let mut s = state_arc.state.lock().unwrap();
+            match album_mut(&mut s, id) {
+                Some(album) if album.status == "pending" => album.status = "downloading".into(),
+                _ => continue,
+            }
+        }
+        state_arc.journal.set_status(id, "downloading");
+
+        match &item {
+            QueueItem::Album(req) => {
+                let result = download_album(&app, &state_arc, id, req);
+                let stopped = state_arc.stop_requests.lock().unwrap().remove(&id);
+                match result {
+                    Ok(_) => {
+                        let mut s = state_arc.state.lock().unwrap();
+                        if let Some(album) = album_mut(&mut s, id) {
+                            album.status = "complete".into();
+                        }
+                        state_arc.journal.remove(id);
+                    }
+                    Err(_) if stopped.is_some() => {
+                        finish_stopped(&state_arc, id, stopped.unwrap_or("cancelled"));
+                    }
+                    Err(e) => {
+                        let mut s = state_arc.state.lock().unwrap();
+                        if let Some(album) = album_mut(&mut s, id) {
+                            album.status = "error".into();
+                            album.error = Some(e.clone());
+                        }
+                        journal_failure(&state_arc, id);
+                        let _ = app.emit(
+                            "download-error",
+                            serde_json::json!({
+                                "artist": req.artist,
+                                "album": req.album,
+                                "error": e,
+                            }),
+                        );
+                    }
+                }
+                let position = queue_position(&state_arc, id);
+                let _ = app.emit(
+                    "download-album-complete",
+                    serde_json::json!({
+                        "artist": req.artist,
+                        "album": req.album,
+                        "albumIndex": position.map(|(index, _)| index),
+                        "totalAlbums": position.map(|(_, total)| total),
+                    }),
+                );
+            }
+            QueueItem::Song { song, video_id, source } => {
+                emit_progress(
+                    &app,
+                    &state_arc,
+                    id,
+                    DownloadProgress {
+                        artist: song.artist.clone(),
+                        album: format!("{} (Single)", song.title),
+                        total_tracks: 1,
+                        track_name: song.title.clone(),
+                        status: "downloading".into(),
+                        ..Default::default()
+                    },
+                );
+
+                let result = download_single_song(&app, &state_arc, song, video_id, source, id);
+                let stopped = state_arc.stop_requests.lock().unwrap().remove(&id);
+                match result {
+                    Err(_) if stopped.is_some() => {
+                        finish_stopped(&state_arc, id, stopped.unwrap_or("cancelled"));
+                    }
+                    Ok(_) => {
+                        let mut s = state_arc.state.lock().unwrap();
+                        if let Some(album) = album_mut(&mut s, id) {
+                            album.status = "complete".into();
+                            album.completed_tracks = 1;
+                        }
+                        state_arc.journal.remove(id);
+                    }
+                    Err(e) => {
+                        let mut s = state_arc.state.lock().unwrap();
+                        if let Some(album) = album_mut(&mut s, id) {
+                            album.status = "error".into();
+                            album.error = Some(e.clone());
+                        }
+                        journal_failure(&state_arc, id);
+                        let _ = app.emit(
+                            "download-error",
+                            serde_json::json!({
+                                "artist": song.artist,
+                                "album": song.title,
+                                "error": e,
+                            }),
+                        );
+                    }
+                }
+                let position = queue_position(&state_arc, id);
+                let _ = app.emit(
+                    "download-album-complete",
+                    serde_json::json!({
+                        "artist": song.artist,
+                        "album": song.title,
+                        "albumIndex": position.map(|(index, _)| index),
+                        "totalAlbums": position.map(|(_, total)| total),
+                    }),
+                );
+            }
+        }
+        // Drop a stop request that arrived too late to matter
+        state_arc.stop_requests.lock().unwrap().remove(&id);
+    };
+
+    if !last_worker {
+        return;
+    }
+
+    // Check if there are any items still pending (shouldn't be, but just in case)
+    let any_pending = {
+        let s = state_arc.state.lock().unwrap();
+        s.albums.iter().any(|a| a.status == "pending" || a.status == "downloading")
+    };
+
+    if !any_pending {
+        {
+            let mut s = state_arc.state.lock().unwrap();
+            s.is_active = false;
+        }
+        let _ = app.emit("download-all-complete", ());
+        request_rescan(&app, &state_arc);
+    }
+}
+
+/// Ask for a library rescan once things settle, if the config wants one.
+fn request_rescan(app: &AppHandle, inner: &Arc<DownloaderStateInner>) {
+    let (auto_scan, delay) = {
+        let config = inner.config.lock().unwrap();
+        (config.auto_scan, Duration::from_secs(config.scan_delay_secs))
+    };
+    if auto_scan {
+        inner.rescanner.request(app, delay);
+    }
+}
+
+/// How often a worker outside the download window checks it again.
+const WINDOW_POLL: Duration = Duration::from_secs(15);
+
+/// Block while the queue is outside its download window. Returns early once
+/// everything is cancelled or nothing is left to wait for.
+fn wait_for_window(app: &AppHandle, inner: &DownloaderStateInner) {
+    let mut waiting = false;
+    loop {
+        let window = inner.config.lock().unwrap().download_window.clone();
+        let give_up =
+            *inner.cancel.lock().unwrap() || inner.pending_queue.lock().unwrap().is_empty();
+        match window.filter(|w| !give_up && !w.is_open_now()) {
+            Some(window) => {
+                if !waiting {
+                    waiting = true;
+                    inner.state.lock().unwrap().waiting_for_window = Some(window.start.clone());
+                    let _ = app.emit(
+                        "download-waiting",
+                        serde_json::json!({ "opensAt": window.start }),
+                    );
+                }
+                std::thread::sleep(WINDOW_POLL);
+            }
+            None => {
+                if waiting {
+                    inner.state.lock().unwrap().waiting_for_window = None;
+                }
+                return;
+            }
+        }
+    }
+}
+
+/// Leave an item stopped by `downloader_cancel_item`/`downloader_pause_item`
+/// in the requested state.
+fn finish_stopped(inner: &DownloaderStateInner, id: u64, status: &str) {
+    let mut s = inner.state.lock().unwrap();
+    if let Some(album) = album_mut(&mut s, id) {
+        album.status = status.into();
+        album.active_tracks.clear();
+    }
+    if status == "paused" {
+        inner.journal.set_status(id, "paused");
+    } else {
+        inner.journal.remove(id);
+    }
+}
+
+/// Record a failed item: cancelled ones are forgotten, others stay resumable.
+fn journal_failure(inner: &DownloaderStateInner, id: u64) {
+    if *inner.cancel.lock().unwrap() {
+        inner.journal.remove(id);
+    } else {
+        inner.journal.set_status(id, "error");
+    }
+}
+
+/// Add items to the queue display, the journal and the pending queue.
+fn enqueue(inner: &DownloaderStateInner, items: Vec<QueueItem>) -> Result<(), String> {
+    let items: Vec<(u64, QueueItem)> = items
+        .into_iter()
+        .map(|item| (inner.next_id.fetch_add(1, Ordering::Relaxed), item))
+        .collect();
+    {
+        let mut s = inner.state.lock().map_err(|e| e.to_string())?;
+        s.is_active = true;
+        for (id, item) in &items {
+            let album = item.album_state(*id, "pending");
+            inner.journal.add(*id, item.clone(), album.total_tracks);
+            s.albums.push(album);
+        }
+    }
+    let mut queue = inner.pending_queue.lock().map_err(|e| e.to_string())?;
+    queue.extend(items);
+    Ok(())
+}
+
+// ────────────────────────────────────────────────────────────────────────────
+// Watchlist
+// ────────────────────────────────────────────────────────────────────────────
+
+/// How often the scheduler looks for followed artists due a check.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Payload of `downloader:new-release`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewRelease {
+    pub artist_id: String,
+    pub artist: String,
+    pub album: MbAlbum,
+    /// Whether it was put in the download queue
+    pub queued: bool,
+}
+
+fn run_watch_scheduler(app: AppHandle, inner: Arc<DownloaderStateInner>) {
+    // Let startup finish before going to MusicBrainz
+    std::thread::sleep(Duration::from_secs(60));
+    loop {
+        let interval = inner.config.lock().unwrap().watch_interval_hours;
+        if interval > 0 {
+            let due = inner.watchlist.due(interval);
+            if !due.is_empty() {
+                check_watched_artists(&app, &inner, &due);
+            }
+        }
+        std::thread::sleep(WATCH_POLL_INTERVAL);
+    }
+}
+
+/// Look for new releases from `artists`, emit `downloader:new-release` for
+/// each one the library doesn't have, and queue those the config asks for.
+/// Ends with `downloader:watchlist-checked`.
+fn check_watched_artists(app: &AppHandle, inner: &Arc<DownloaderStateInner>, artists: &[WatchedArtist]) {
+    let _checking = inner.watchlist.checking.lock().unwrap();
+    let config = inner.config.lock().unwrap().clone();
+    let root = Path::new(&config.music_dir);
+    let mut queued = Vec::new();
+    let mut found = 0;
+
+    for artist in artists {
+        let discography = match fetch_discography(&artist.id) {
+            Ok(d) => d,
+            Err(e) => {
+                log::warn!("Failed to check {} for new releases: {e}", artist.name);
+                continue;
+            }
+        };
+        let new = inner.watchlist.new_releases(&artist.id, &discography);
+        if new.is_empty() {
+            continue;
+        }
+        let new: Vec<MbAlbum> = with_library(inner, root, |library| {
+            new.into_iter()
+                .filter(|album| !library.has_album(&artist.name, &album.title))
+                .collect()
+        });
+
+        found += new.len();
+        for album in new {
+            let queue = config.auto_enqueues(&album);
+            log::info!(
+                "New release from {}: {} ({}){}",
+                artist.name,
+                album.title,
+                album.release_date,
+                if queue { ", queued" } else { "" }
+            );
+            if queue {
+                queued.push(QueueItem::Album(AlbumRequest {
+                    artist: artist.name.clone(),
+                    album: album.title.clone(),
+                    year: album.year.clone(),
+                    genre: if artist.genre.is_empty() { "Rock".into() } else { artist.genre.clone() },
+                    tracks: None,
+                    release_group_id: Some(album.id.clone()),
+                    release_id: None,
+                    duplicate_policy: None,
+                    only_tracks: None,
+                    source: Source::default(),
+                }));
+            }
+            let _ = app.emit(
+                "downloader:new-release",
+                NewRelease {
+                    artist_id: artist.id.clone(),
+                    artist: artist.name.clone(),
+                    album,
+                    queued: queue,
+                },
+            );
+        }
+    }
+
+    if !queued.is_empty() {
+        match enqueue(inner, queued) {
+            Ok(()) => ensure_worker(app, inner),
+            Err(e) => log::warn!("Failed to queue new releases: {e}"),
+        }
+    }
+    let _ = app.emit(
+        "downloader:watchlist-checked",
+        serde_json::json!({ "artists": artists.len(), "newReleases": found }),
+    );
+}
+
+// ────────────────────────────────────────────────────────────────────────────
+// Tauri Commands
+// ────────────────────────────────────────────────────────────────────────────
+
+/// Step 1: Search for artists by name.
+#[tauri::command]
+pub fn downloader_search_artist(artist: String) -> Result<Vec<MbArtist>, String> {
+    let encoded = urlencoding::encode(&artist);
+    let url = format!(
+        "artist/?query={encoded}&fmt=json&limit=8"
+    );
+    let data = mb_get(&url)?;
+
+    let artists = data["artists"]
+        .as_array()
+        .ok_or("No artists in response")?;
+
+    let results: Vec<MbArtist> = artists
+        .iter()
+        .filter_map(|a| {
+            Some(MbArtist {
+                id: a["id"].as_str()?.to_string(),
+                name: a["name"].as_str()?.to_string(),
+                disambiguation: a["disambiguation"].as_str().unwrap_or("").to_string(),
+            })
+        })
+        .collect();
+
+    Ok(results)
+}
+
+/// Step 2: Get all release-groups for an artist by their MB ID.
+#[tauri::command]
+pub fn downloader_get_discography(artist_id: String) -> Result<Vec<MbAlbum>, String> {
+    fetch_discography(&artist_id)
+}
+
+fn fetch_discography(artist_id: &str) -> Result<Vec<MbAlbum>, String> {
+    let mut all_albums: Vec<MbAlbum> = Vec::new();
+    let mut offset = 0;
+    let limit = 100;
+
+    loop {
+        let url = format!(
+            "release-group/?artist={artist_id}&fmt=json&limit={limit}&offset={offset}"
+        );
+        let data = mb_get(&url)?;
+
+        let groups = data["release-groups"]
+            .as_array()
+            .ok_or("No release-groups in response")?;
+
+        if groups.is_empty() {
+            break;
+        }
+
+        for rg in groups {
+            let title = match rg["title"].as_str() {
+                Some(t) => t.to_string(),
+                None => continue,
+            };
+            let id = match rg["id"].as_str() {
+                Some(i) => i.to_string(),
+                None => continue,
+            };
+            let release_date = rg["first-release-date"].as_str().unwrap_or("").to_string();
+            let year = release_date.chars().take(4).collect::<String>();
+            let primary_type = rg["primary-type"]
+                .as_str()
+                .unwrap_or("")
+                .to_string();
+            let secondary_types: Vec<String> = rg["secondary-types"]
+                .as_array()
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            all_albums.push(MbAlbum {
+                id,
+                title,
+                year,
+                release_type: primary_type,
+                secondary_types,
+                release_date,
+            });
+        }
+
+        let total = data["release-group-count"].as_u64().unwrap_or(0) as usize;
+        offset += limit;
+        if offset >= total {
+            break;
+        }
+    }
+
+    all_albums.sort_by(|a, b| a.year.cmp(&b.year));
+    all_albums.dedup_by(|a, b| a.title.to_lowercase() == b.title.to_lowercase());
+
+    Ok(all_albums)
+}
+
+/// A track of a requested album that the library already has.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnedTrack {
+    pub track_index: usize,
+    pub title: String,
+    pub disc: usize,
+    pub track: usize,
+    /// Existing files that match
+    pub paths: Vec<String>,
+}
+
+/// Check which tracks of `album` are already in the library, so the user can
+/// pick a duplicate policy before queueing it.
+#[tauri::command]
+pub fn downloader_find_owned(
+    state: tauri::State<'_, DownloaderState>,
+    album: AlbumRequest,
+) -> Result<Vec<OwnedTrack>, String> {
+    let config = state.0.config.lock().map_err(|e| e.to_string())?.clone();
+    config.validate()?;
+    let template = config.template()?;
+    let tracks = album_tracklist(&album)?;
+    let paths = album_track_paths(&config, &template, &album, &tracks);
+    let root = Path::new(&config.music_dir);
+
+    Ok(with_library(&state.0, root, |library| {
+        tracks
+            .iter()
+            .zip(&paths)
+            .enumerate()
+            .filter_map(|(i, (track, path))| {
+                let duration = track
+                    .length_ms
+                    .map(|ms| ms as f64 / 1000.0)
+                    .filter(|_| config.duplicate_match_duration);
+                let mut found: Vec<String> = library
+                    .find(&album.artist, &album.album, &track.title, duration, path)
+                    .iter()
+                    .map(|f| f.path.to_string_lossy().to_string())
+                    .collect();
+                if path.exists() {
+                    found.insert(0, path.to_string_lossy().to_string());
+                }
+                (!found.is_empty()).then(|| OwnedTrack {
+                    track_index: i,
+                    title: track.title.clone(),
+                    disc: track.disc,
+                    track: track.track,
+                    paths: found,
+                })
+            })
+            .collect()
+    }))
+}
+
+/// List the editions of a release group, best default first.
+#[tauri::command]
+pub fn downloader_get_releases(release_group_id: String) -> Result<Vec<MbRelease>, String> {
+    fetch_group_releases(&release_group_id)
+}
+
+#[tauri::command]
+pub fn downloader_get_tracklist(
+    artist: String,
+    album: String,
+    release_group_id: Option<String>,
+    release_id: Option<String>,
+) -> Result<Vec<AlbumTrack>, String> {
+    album_tracklist(&AlbumRequest {
+        artist,
+        album,
+        year: String::new(),
+        genre: String::new(),
+        tracks: None,
+        release_group_id,
+        release_id,
+        duplicate_policy: None,
+        only_tracks: None,
+        source: Source::YtDlp,
+    })
+}
+
+/// Search YouTube for songs matching a query.
+#[tauri::command]
+pub fn downloader_search_songs(query: String) -> Result<Vec<YtSearchResult>, String> {
+    let ytdlp = yt_dlp_path();
+    let output = Command::new(&ytdlp)
+        .args([
+            "--no-update",
+            "--flat-playlist",
+            "-j",
+            &format!("ytsearch10:{query}"),
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run yt-dlp: {e}"))?;
+
+    if !output.status.success() {
+        return Err("yt-dlp search failed".into());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut results = Vec::new();
+
+    for line in stdout.lines() {
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
+            let id = json["id"].as_str().unwrap_or("").to_string();
+            let title = json["title"].as_str().unwrap_or("").to_string();
+            let duration_secs = json["duration"].as_f64().unwrap_or(0.0);
+            let mins = (duration_secs / 60.0).floor() as u32;
+            let secs = (duration_secs % 60.0) as u32;
+            let duration = format!("{mins}:{secs:02}");
+            let channel = json["channel"].as_str()
+                .or_else(|| json["uploader"].as_str())
+                .unwrap_or("")
+                .to_string();
+
+            if !id.is_empty() && !title.is_empty() {
+                results.push(YtSearchResult {
+                    id,
+                    title,
+                    duration,
+                    channel,
+                });
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Enqueue albums for download. Can be called while downloads are already in progress.
+#[tauri::command]
+pub fn downloader_start(
+    app: AppHandle,
+    state: tauri::State<'_, DownloaderState>,
+    albums: Vec<AlbumRequest>,
+) -> Result<(), String> {
+    enqueue(&state.0, albums.into_iter().map(QueueItem::Album).collect())?;
+
+    ensure_worker(&app, &state.0);
+    Ok(())
+}
+
+/// Enqueue individual songs for download. Can be called while downloads are already in progress.
+#[tauri::command]
+pub fn downloader_download_songs(
+    app: AppHandle,
+    state: tauri::State<'_, DownloaderState>,
+    songs: Vec<SongRequest>,
+    video_ids: Vec<String>,
+    source: Option<Source>,
+) -> Result<(), String> {
+    let source = source.unwrap_or_default();
+    let items = songs
+        .into_iter()
+        .zip(video_ids)
+        .map(|(song, video_id)| QueueItem::Song { song, video_id, source: source.clone() })
+        .collect();
+    enqueue(&state.0, items)?;
+
+    ensure_worker(&app, &state.0);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn downloader_get_status(
+    state: tauri::State<'_, DownloaderState>,
+) -> Result<DownloadState, String> {
+    let s = state.0.state.lock().map_err(|e| e.to_string())?;
+    Ok(s.clone())
+}
+
+#[tauri::command]
+pub fn downloader_cancel(state: tauri::State<'_, DownloaderState>) -> Result<(), String> {
+    let mut cancel = state.0.cancel.lock().map_err(|e| e.to_string())?;
+    *cancel = true;
+    Ok(())
+}
+
+/// Cancel one item. Queued or paused items are dropped at once; a running
+/// one stops after the tracks currently in flight.
+#[tauri::command]
+pub fn downloader_cancel_item(state: tauri::State<'_, DownloaderState>, id: u64) -> Result<(), String> {
+    stop_item(&state.0, id, "cancelled")
+}
+
+/// Pause one item; `downloader_resume` picks it up again.
+#[tauri::command]
+pub fn downloader_pause_item(state: tauri::State<'_, DownloaderState>, id: u64) -> Result<(), String> {
+    stop_item(&state.0, id, "paused")
+}
+
+fn stop_item(inner: &DownloaderStateInner, id: u64, status: &'static str) -> Result<(), String> {
+    let mut s = inner.state.lock().map_err(|e| e.to_string())?;
+    let album = s
+        .albums
+        .iter_mut()
+        .find(|a| a.id == id)
+        .ok_or("No such download")?;
+
+    match album.status.as_str() {
+        "downloading" => {
+            inner.stop_requests.lock().map_err(|e| e.to_string())?.insert(id, status);
+        }
+        "pending" | "paused" => {
+            if album.status == "paused" && status == "paused" {
+                return Ok(());
+            }
+            album.status = status.into();
+            inner
+                .pending_queue
+                .lock()
+                .map_err(|e| e.to_string())?
+                .retain(|(queued, _)| *queued != id);
+            if status == "paused" {
+                inner.journal.set_status(id, "paused");
+            } else {
+                inner.journal.remove(id);
+            }
+        }
+        other => return Err(format!("Download is already {other}")),
+    }
+    Ok(())
+}
+
+/// Move a queued item `offset` places towards the front (negative) or back.
+#[tauri::command]
+pub fn downloader_move_item(
+    state: tauri::State<'_, DownloaderState>,
+    id: u64,
+    offset: i64,
+) -> Result<(), String> {
+    let mut s = state.0.state.lock().map_err(|e| e.to_string())?;
+    let mut queue = state.0.pending_queue.lock().map_err(|e| e.to_string())?;
+
+    let from = queue
+        .iter()
+        .position(|(queued, _)| *queued == id)
+        .ok_or("Download is not waiting in the queue")?;
+    let to = (from as i64 + offset).clamp(0, queue.len() as i64 - 1) as usize;
+    let item = queue.remove(from);
+    queue.insert(to, item);
+
+    // Mirror the order in the album list. Only the slots of queued items are
+    // reshuffled, so running items keep their index.
+    let slots: Vec<usize> = s
+        .albums
+        .iter()
+        .enumerate()
+        .filter(|(_, a)| queue.iter().any(|(queued, _)| *queued == a.id))
+        .map(|(i, _)| i)
+        .collect();
+    let ordered: Vec<AlbumDownloadState> = queue
+        .iter()
+        .filter_map(|(queued, _)| s.albums.iter().find(|a| a.id == *queued).cloned())
+        .collect();
+    for (slot, album) in slots.into_iter().zip(ordered) {
+        s.albums[slot] = album;
+    }
+    Ok(())
+}
+
+/// Queue the failed tracks of a finished album again.
+#[tauri::command]
+pub fn downloader_retry_failed(
+    app: AppHandle,
+    state: tauri::State<'_, DownloaderState>,
+    id: u64,
+) -> Result<(), String> {
+    let item = {
+        let mut s = state.0.state.lock().map_err(|e| e.to_string())?;
+        let album = s
+            .albums
+            .iter_mut()
+            .find(|a| a.id == id)
+            .ok_or("No such download")?;
+        if album.status != "complete" && album.status != "error" {
+            return Err("Only finished downloads can be retried".into());
+        }
+        let failed: Vec<usize> = album
+            .tracks
+            .iter()
+            .filter(|t| t.status == "error")
+            .map(|t| t.track_index)
+            .collect();
+        let item = match album.queue_item.clone() {
+            Some(QueueItem::Album(mut req)) if !failed.is_empty() => {
+                req.only_tracks = Some(failed);
+                QueueItem::Album(req)
+            }
+            // Albums that failed before their tracklist, and single songs, go again whole
+            Some(item) if album.tracks.is_empty() => item,
+            Some(_) => return Err("No failed tracks to retry".into()),
+            None => return Err("Download can't be retried".into()),
+        };
+        album.status = "pending".into();
+        album.error = None;
+        s.is_active = true;
+        item
+    };
+
+    state.0.journal.remove(id);
+    state.0.journal.add(id, item.clone(), 0);
+    state
+        .0
+        .pending_queue
+        .lock()
+        .map_err(|e| e.to_string())?
+        .push((id, item));
+
+    ensure_worker(&app, &state.0);
+    Ok(())
+}
+
+/// Remove completed, errored, and cancelled items from the queue display.
+#[tauri::command]
+pub fn downloader_clear_finished(state: tauri::State<'_, DownloaderState>) -> Result<(), String> {
+    let mut s = state.0.state.lock().map_err(|e| e.to_string())?;
+    s.albums.retain(|a| a.status != "complete" && a.status != "error" && a.status != "cancelled");
+    if s.albums.is_empty() {
+        s.is_active = false;
+    }
+    let kept: Vec<u64> = s.albums.iter().map(|a| a.id).collect();
+    state.0.journal.retain(|e| kept.contains(&e.id));
+    Ok(())
+}
+
+/// Re-queue paused items, such as those restored from a previous run.
+///
+/// Resumes all of them, or only `ids` when given. Tracks already on disk are
+/// skipped as done when the album runs again.
+#[tauri::command]
+pub fn downloader_resume(
+    app: AppHandle,
+    state: tauri::State<'_, DownloaderState>,
+    ids: Option<Vec<u64>>,
+) -> Result<(), String> {
+    let entries = state.0.journal.entries();
+    let mut resumed = Vec::new();
+    {
+        let mut s = state.0.state.lock().map_err(|e| e.to_string())?;
+        for album in s.albums.iter_mut().filter(|a| a.status == "paused") {
+            if ids.as_ref().is_some_and(|ids| !ids.contains(&album.id)) {
+                continue;
+            }
+            if let Some(entry) = entries.iter().find(|e| e.id == album.id) {
+                album.status = "pending".into();
+                album.error = None;
+                resumed.push((entry.id, entry.item.clone()));
+            }
+        }
+        if resumed.is_empty() {
+            return Ok(());
+        }
+        s.is_active = true;
+    }
+    for (id, _) in &resumed {
+        state.0.journal.set_status(*id, "pending");
+    }
+    state
+        .0
+        .pending_queue
+        .lock()
+        .map_err(|e| e.to_string())?
+        .extend(resumed);
+
+    ensure_worker(&app, &state.0);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn downloader_get_config(
+    state: tauri::State<'_, DownloaderState>,
+) -> Result<DownloaderConfig, String> {
+    let config = state.0.config.lock().map_err(|e| e.to_string())?;
+    Ok(config.clone())
+}
+
+/// Validate and persist new settings. Downloads already running keep the old ones.
+#[tauri::command]
+pub fn downloader_set_config(
+    app: AppHandle,
+    state: tauri::State<'_, DownloaderState>,
+    config: DownloaderConfig,
+) -> Result<(), String> {
+    config.validate()?;
+    config::save(&app, &config)?;
+    musicbrainz::client().configure(&config);
+    *state.0.config.lock().map_err(|e| e.to_string())? = config;
+    Ok(())
+}
+
+/// Show where an album's tracks would be saved.
+///
+/// `path_template` previews an unsaved template; it defaults to the saved one.
+#[tauri::command]
+pub fn downloader_preview_paths(
+    state: tauri::State<'_, DownloaderState>,
+    album: AlbumRequest,
+    path_template: Option<String>,
+) -> Result<Vec<String>, String> {
+    let config = state.0.config.lock().map_err(|e| e.to_string())?.clone();
+    let template = match path_template {
+        Some(t) => PathTemplate::parse(&t)?,
+        None => config.template()?,
+    };
+    let tracks = album_tracklist(&album)?;
+    let paths = album_track_paths(&config, &template, &album, &tracks);
+    Ok(paths
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect())
+}
+
+/// Progress of `downloader_import_apply`, one event per file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportProgress {
+    pub done: usize,
+    pub total: usize,
+    pub file: String,
+    /// Where the file went in the library; `None` if it was skipped or failed
+    pub written: Option<String>,
+}
+
+/// Read a folder (or `.zip`) of existing music and group it into albums.
+/// Runs on the blocking pool, since large folders take a while to read.
+#[tauri::command]
+pub async fn downloader_import_scan(
+    state: tauri::State<'_, DownloaderState>,
+    path: String,
+) -> Result<Vec<ImportAlbum>, String> {
+    let config = state.0.config.lock().map_err(|e| e.to_string())?.clone();
+    config.validate()?;
+    tauri::async_runtime::spawn_blocking(move || import::scan(Path::new(path.trim()), &config))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Look up a scanned album on MusicBrainz and pair its files with tracks.
+/// `release_id` switches to another edition during review.
+#[tauri::command]
+pub async fn downloader_import_match(
+    album: ImportAlbum,
+    release_id: Option<String>,
+) -> Result<ImportMatch, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        import::match_album(&album, release_id.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Import reviewed albums into the library in the background. Emits
+/// `import-progress` per file and `import-complete` with an [`ImportReport`].
+#[tauri::command]
+pub fn downloader_import_apply(
+    app: AppHandle,
+    state: tauri::State<'_, DownloaderState>,
+    albums: Vec<ImportPlan>,
+    mode: ImportMode,
+) -> Result<(), String> {
+    let config = state.0.config.lock().map_err(|e| e.to_string())?.clone();
+    config.validate()?;
+    let template = config.template()?;
+    let inner = Arc::clone(&state.0);
+
+    std::thread::spawn(move || {
+        let root = Path::new(&config.music_dir);
+        let total: usize = albums.iter().map(|a| a.album.files.len()).sum();
+        let mut done = 0;
+        let mut report = ImportReport::default();
+        for plan in &albums {
+            import::apply_album(&config, &template, plan, mode, &mut report, &mut |file, written| {
+                done += 1;
+                if let Some(path) = written {
+                    // Keep a cached index current; a stale one rescans anyway
+                    if let Some(library) = inner.library.lock().unwrap().as_mut() {
+                        library.add_file(path);
+                    }
+                }
+                let _ = app.emit(
+                    "import-progress",
+                    ImportProgress {
+                        done,
+                        total,
+                        file: file.path.clone(),
+                        written: written.map(|p| p.to_string_lossy().to_string()),
+                    },
+                );
+            });
+        }
+        staging::clear_imports(root);
+        log::info!(
+            "Imported {} files ({} skipped, {} failed)",
+            report.imported,
+            report.skipped.len(),
+            report.failed.len()
+        );
+        let imported = report.imported > 0;
+        let _ = app.emit("import-complete", report);
+        if imported {
+            request_rescan(&app, &inner);
+        }
+    });
+    Ok(())
+}
+
+/// Followed artists, in the order they were followed.
+#[tauri::command]
+pub fn downloader_get_watchlist(
+    state: tauri::State<'_, DownloaderState>,
+) -> Result<Vec<WatchedArtist>, String> {
+    Ok(state.0.watchlist.artists())
+}
+
+/// Follow an artist. Their current discography counts as seen, so only
+/// releases that appear from now on are reported.
+#[tauri::command]
+pub fn downloader_follow_artist(
+    state: tauri::State<'_, DownloaderState>,
+    artist_id: String,
+    name: String,
+    genre: Option<String>,
+) -> Result<WatchedArtist, String> {
+    let discography = fetch_discography(&artist_id)?;
+    Ok(state
+        .0
+        .watchlist
+        .follow(&artist_id, &name, genre.as_deref().unwrap_or(""), &discography))
+}
+
+#[tauri::command]
+pub fn downloader_unfollow_artist(
+    state: tauri::State<'_, DownloaderState>,
+    artist_id: String,
+) -> Result<(), String> {
+    state.0.watchlist.unfollow(&artist_id);
+    Ok(())
+}
+
+/// Check every followed artist now, in the background.
+#[tauri::command]
+pub fn downloader_check_watchlist(
+    app: AppHandle,
+    state: tauri::State<'_, DownloaderState>,
+) -> Result<(), String> {
+    let inner = Arc::clone(&state.0);
+    std::thread::spawn(move || {
+        let artists = inner.watchlist.artists();
+        check_watched_artists(&app, &inner, &artists);
+    });
+    Ok(())
+}
+
+/// Set the Subsonic server to rescan after downloads, or `None` to stop.
+#[tauri::command]
+pub fn downloader_set_scan_server(
+    state: tauri::State<'_, DownloaderState>,
+    server: Option<ScanServer>,
+) -> Result<(), String> {
+    state.0.rescanner.set_server(server);
+    Ok(())
+}
+
+/// Rescan the given server now, in the background. It stays registered for
+/// later rescans. Emits the same events as automatic rescans.
+#[tauri::command]
+pub fn downloader_trigger_scan(
+    app: AppHandle,
+    state: tauri::State<'_, DownloaderState>,
+    server_url: String,
+    username: String,
+    password: String,
+) -> Result<(), String> {
+    let rescanner = &state.0.rescanner;
+    rescanner.set_server(Some(ScanServer {
+        url: server_url,
+        username,
+        password,
+    }));
+    rescanner.request(&app, Duration::ZERO);
+    Ok(())
+}
+
+// ────────────────────────────────────────────────────────────────────────────
+// Download logic
+// ────────────────────────────────────────────────────────────────────────────
+
+fn sanitize_filename(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            _ => c,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Full paths for each track of an album, in tracklist order.
+fn album_track_paths(
+    config: &DownloaderConfig,
+    template: &PathTemplate,
+    req: &AlbumRequest,
+    tracks: &[AlbumTrack],
+) -> Vec<PathBuf> {
+    let total_discs = total_discs(tracks);
+    tracks
+        .iter()
+        .map(|track| {
+            let fields = TrackFields {
+                artist: &req.artist,
+                album_artist: &req.artist,
+                album: &req.album,
+                year: &req.year,
+                genre: &req.genre,
+                title: &track.title,
+                track: track.track,
+                total_tracks: track.disc_tracks,
+                disc: track.disc,
+                total_discs,
+                ext: config.audio_format.extension(),
+            };
+            Path::new(&config.music_dir).join(template.render(&fields))
+        })
+        .collect()
+}
+
+// ────────────────────────────────────────────────────────────────────────────
+// Concurrent download helpers
+// ────────────────────────────────────────────────────────────────────────────
+
+/// The queue item `id`, unless it was cleared away meanwhile.
+fn album_mut(s: &mut DownloadState, id: u64) -> Option<&mut AlbumDownloadState> {
+    s.albums.iter_mut().find(|a| a.id == id)
+}
+
+/// Where the item `id` sits in the queue now, and the queue's length.
+fn queue_position(dl_state: &DownloaderStateInner, id: u64) -> Option<(usize, usize)> {
+    let s = dl_state.state.lock().unwrap();
+    let index = s.albums.iter().position(|a| a.id == id)?;
+    Some((index, s.albums.len()))
+}
+
+/// Send `progress` for the item `id`, filling in its current queue position.
+/// Items cleared from the queue meanwhile aren't reported.
+fn emit_progress(app: &AppHandle, dl_state: &DownloaderStateInner, id: u64, progress: DownloadProgress) {
+    let Some((album_index, total_albums)) = queue_position(dl_state, id) else {
+        return;
+    };
+    let _ = app.emit(
+        "download-progress",
+        DownloadProgress {
+            album_index,
+            total_albums,
+            ..progress
+        },
+    );
+}
+
+fn emit_track_progress(
+    app: &AppHandle,
+    dl_state: &DownloaderStateInner,
+    id: u64,
+    req: &AlbumRequest,
+    track_idx: usize,
+    total_tracks: usize,
+    track_name: &str,
+    status: &str,
+    error: Option<&str>,
+) {
+    emit_progress(
+        app,
+        dl_state,
+        id,
+        DownloadProgress {
+            artist: req.artist.clone(),
+            album: req.album.clone(),
+            track_index: track_idx,
+            total_tracks,
+            track_name: track_name.to_string(),
+            status: status.to_string(),
+            error: error.map(|e| e.to_string()),
+            ..Default::default()
+        },
+    );
+}
+
+/// Run `f` on the index of the library at `root`, (re)scanning it if stale.
+fn with_library<T>(
+    dl_state: &DownloaderStateInner,
+    root: &Path,
+    f: impl FnOnce(&mut LibraryIndex) -> T,
+) -> T {
+    let mut library = dl_state.library.lock().unwrap();
+    if !library.as_ref().is_some_and(|l| l.is_fresh(root)) {
+        *library = Some(LibraryIndex::scan(root));
+    }
+    f(library.as_mut().unwrap())
+}
+
+/// Move a track's staged `.lrc` file next to it in the library.
+fn commit_lyrics(music_dir: &Path, lrc: Option<PathBuf>, track_path: &Path) {
+    let Some(lrc) = lrc else {
+        return;
+    };
+    if let Err(e) = staging::commit(music_dir, &lrc, &track_path.with_extension("lrc")) {
+        staging::discard(music_dir, &lrc);
+        log::warn!("Failed to save lyrics for {}: {e}", track_path.display());
+    }
+}
+
+/// Whether the item `id` should stop: everything was cancelled, the item
+/// itself was cancelled or paused, or it is no longer in the queue.
+fn stop_requested(dl_state: &DownloaderStateInner, id: u64) -> bool {
+    if *dl_state.cancel.lock().unwrap() {
+        return true;
+    }
+    if !dl_state.state.lock().unwrap().albums.iter().any(|a| a.id == id) {
+        return true;
+    }
+    dl_state.stop_requests.lock().unwrap().contains_key(&id)
+}
+
+fn set_track_result(
+    dl_state: &DownloaderStateInner,
+    id: u64,
+    track_idx: usize,
+    status: &str,
+    error: Option<&str>,
+) {
+    let mut s = dl_state.state.lock().unwrap();
+    if let Some(t) = album_mut(&mut s, id).and_then(|a| a.tracks.get_mut(track_idx))
+    {
+        t.status = status.to_string();
+        t.error = error.map(|e| e.to_string());
+    }
+}
+
+/// Count a finished track towards its album and disc, and journal it.
+fn record_track_done(
+    dl_state: &DownloaderStateInner,
+    id: u64,
+    track_idx: usize,
+    disc: usize,
+    count: usize,
+) {
+    let mut s = dl_state.state.lock().unwrap();
+    if let Some(album) = album_mut(&mut s, id) {
+        album.completed_tracks = count;
+        if let Some(d) = album.discs.iter_mut().find(|d| d.disc == disc) {
+            d.completed_tracks += 1;
+        }
+        dl_state.journal.track_done(id, track_idx);
+    }
+}
+
+fn add_active_track(
+    dl_state: &DownloaderStateInner,
+    id: u64,
+    track_idx: usize,
+    track_name: &str,
+    status: &str,
+) {
+    let mut s = dl_state.state.lock().unwrap();
+    if let Some(album) = album_mut(&mut s, id) {
+        album.active_tracks.push(ActiveTrack {
+            track_index: track_idx,
+            track_name: track_name.to_string(),
+            status: status.to_string(),
+            matched: None,
+            progress: None,
+        });
+    }
+}
+
+fn update_active_track_status(
+    dl_state: &DownloaderStateInner,
+    id: u64,
+    track_idx: usize,
+    status: &str,
+) {
+    let mut s = dl_state.state.lock().unwrap();
+    if let Some(at) = album_mut(&mut s, id)
+        .and_then(|a| a.active_tracks.iter_mut().find(|t| t.track_index == track_idx))
+    {
+        at.status = status.to_string();
+    }
+}
+
+/// Store a track's live progress and refresh the album and queue estimates.
+fn update_track_progress(
+    dl_state: &DownloaderStateInner,
+    id: u64,
+    track_idx: usize,
+    progress: &TrackProgress,
+) {
+    let mut s = dl_state.state.lock().unwrap();
+    if let Some(at) = album_mut(&mut s, id)
+        .and_then(|a| a.active_tracks.iter_mut().find(|t| t.track_index == track_idx))
+    {
+        at.progress = Some(progress.clone());
+    }
+    refresh_byte_progress(&mut s);
+}
+
+/// Count a finished download's size towards its album.
+fn record_track_bytes(dl_state: &DownloaderStateInner, id: u64, track_idx: usize, bytes: u64) {
+    let mut s = dl_state.state.lock().unwrap();
+    if let Some(album) = album_mut(&mut s, id) {
+        album.finished_bytes += bytes;
+        album.finished_sized += 1;
+        if let Some(at) = album.active_tracks.iter_mut().find(|t| t.track_index == track_idx) {
+            at.progress = None;
+        }
+    }
+    refresh_byte_progress(&mut s);
+}
+
+/// Recompute byte totals, speeds and ETAs from the active tracks.
+///
+/// Sizes of tracks not started yet are guessed from the average of the tracks
+/// sized so far, and albums whose tracklist isn't known yet count as the
+/// average known album length.
+fn refresh_byte_progress(s: &mut DownloadState) {
+    let mut sized_bytes = 0u64;
+    let mut sized_tracks = 0usize;
+    let mut speed = 0.0;
+    let mut remaining_bytes = 0.0;
+
+    for album in s.albums.iter_mut().filter(|a| a.status == "downloading") {
+        let mut downloaded = album.finished_bytes;
+        let mut known_total = album.finished_bytes;
+        let mut sized = album.finished_sized;
+        let mut started = 0;
+        album.speed = 0.0;
+        for p in album.active_tracks.iter().filter_map(|t| t.progress.as_ref()) {
+            downloaded += p.downloaded_bytes;
+            started += 1;
+            if let Some(total) = p.total_bytes {
+                known_total += total;
+                sized += 1;
+            }
+            album.speed += p.speed.unwrap_or(0.0);
+        }
+
+        let unstarted = album
+            .total_tracks
+            .saturating_sub(album.completed_tracks + started);
+        let average = if sized > 0 { known_total / sized as u64 } else { 0 };
+        album.downloaded_bytes = downloaded;
+        album.total_bytes = known_total + average * unstarted as u64;
+        album.eta_secs = (album.speed > 0.0 && sized > 0).then(|| {
+            (album.total_bytes.saturating_sub(downloaded) as f64 / album.speed) as u64
+        });
+
+        sized_bytes += known_total;
+        sized_tracks += sized;
+        speed += album.speed;
+        remaining_bytes += album.total_bytes.saturating_sub(downloaded) as f64;
+    }
+
+    let known_lengths: Vec<usize> = s
+        .albums
+        .iter()
+        .map(|a| a.total_tracks)
+        .filter(|n| *n > 0)
+        .collect();
+    let average_album = known_lengths.iter().sum::<usize>() / known_lengths.len().max(1);
+    let average_track = sized_bytes as f64 / sized_tracks.max(1) as f64;
+    for album in s.albums.iter().filter(|a| a.status == "pending") {
+        let tracks = if album.total_tracks > 0 { album.total_tracks } else { average_album };
+        remaining_bytes += tracks as f64 * average_track;
+    }
+
+    s.eta_secs = (speed > 0.0 && sized_tracks > 0).then(|| (remaining_bytes / speed) as u64);
+}
+
+fn set_active_track_match(
+    dl_state: &DownloaderStateInner,
+    id: u64,
+    track_idx: usize,
+    matched: &TrackMatch,
+) {
+    let mut s = dl_state.state.lock().unwrap();
+    if let Some(at) = album_mut(&mut s, id)
+        .and_then(|a| a.active_tracks.iter_mut().find(|t| t.track_index == track_idx))
+    {
+        at.matched = Some(matched.clone());
+    }
+}
+
+fn remove_active_track(dl_state: &DownloaderStateInner, id: u64, track_idx: usize) {
+    let mut s = dl_state.state.lock().unwrap();
+    if let Some(album) = album_mut(&mut s, id) {
+        album.active_tracks.retain(|t| t.track_index != track_idx);
+    }
+}
+
+// ────────────────────────────────────────────────────────────────────────────
+// Per-track download logic (called from concurrent workers)
+// ────────────────────────────────────────────────────────────────────────────
+
+/// What the tracks of one album download share.
+struct AlbumJob<'a> {
+    app: &'a AppHandle,
+    dl_state: &'a DownloaderStateInner,
+    /// The queue item, looked up by id since its position can change
+    id: u64,
+    req: &'a AlbumRequest,
+    source: &'a dyn DownloadSource,
+    config: &'a DownloaderConfig,
+    total_tracks: usize,
+    total_discs: usize,
+    /// The MusicBrainz release the tracklist came from, when known
+    release_id: Option<&'a str>,
+    cover: Option<&'a [u8]>,
+    /// Tracks finished so far, including those left out of a retry
+    completed: AtomicUsize,
+    /// Set once a track sees the album stopped
+    cancelled: AtomicBool,
+}
+
+impl AlbumJob<'_> {
+    fn emit(&self, track_idx: usize, track_name: &str, status: &str, error: Option<&str>) {
+        emit_track_progress(
+            self.app, self.dl_state, self.id, self.req, track_idx, self.total_tracks, track_name,
+            status, error,
+        );
+    }
+
+    fn should_stop(&self) -> bool {
+        stop_requested(self.dl_state, self.id)
+    }
+
+    /// Count a track as finished with `status` without downloading it.
+    fn finish_early(&self, track_idx: usize, track: &AlbumTrack, status: &str) {
+        let new_count = self.completed.fetch_add(1, Ordering::Relaxed) + 1;
+        record_track_done(self.dl_state, self.id, track_idx, track.disc, new_count);
+        set_track_result(self.dl_state, self.id, track_idx, status, None);
+        self.emit(track_idx, &track.title, status, None);
+    }
+
+    fn fail(&self, track_idx: usize, track_name: &str, error: &str) {
+        set_track_result(self.dl_state, self.id, track_idx, "error", Some(error));
+        self.emit(track_idx, track_name, "error", Some(error));
+    }
+}
+
+fn process_single_track(job: &AlbumJob, track_idx: usize, track: &AlbumTrack, filepath: &Path) {
+    let AlbumJob { app, dl_state, id, req, source, config, .. } = *job;
+    let track_name = track.title.as_str();
+
+    // Skip if exists, in whatever format the source delivered it last time
+    if library::existing_audio(filepath).is_some() {
+        job.finish_early(track_idx, track, "done");
+        return;
+    }
+
+    let expected_secs = track.length_ms.map(|ms| ms as f64 / 1000.0);
+
+    // Already in the library under another name, folder or format
+    let policy = req.duplicate_policy.unwrap_or(config.duplicate_policy);
+    let mut owned = if policy == DuplicatePolicy::KeepBoth {
+        vec![]
+    } else {
+        let duration = expected_secs.filter(|_| config.duplicate_match_duration);
+        with_library(dl_state, Path::new(&config.music_dir), |library| {
+            library.find(&req.artist, &req.album, track_name, duration, filepath)
+        })
+    };
+    if !owned.is_empty() && policy == DuplicatePolicy::Skip {
+        job.finish_early(track_idx, track, "owned");
+        return;
+    }
+
+    let music_dir = Path::new(&config.music_dir);
+    let staged = staging::staged_path(music_dir, filepath);
+    if let Err(e) = staging::prepare(music_dir, &staged) {
+        job.fail(track_idx, track_name, &e);
+        return;
+    }
+
+    // Register as active and look the track up in the source
+    add_active_track(dl_state, id, track_idx, track_name, "searching");
+    job.emit(track_idx, track_name, "searching", None);
+
+    let should_stop = || job.should_stop();
+    let ctx = FetchContext {
+        config,
+        should_stop: &should_stop,
+    };
+    let found = match &track.source_ref {
+        Some(reference) => source.resolve(reference, &ctx).map(Some),
+        None => {
+            let query = TrackQuery {
+                artist: &req.artist,
+                title: track_name,
+                expected_secs,
+            };
+            source.search(&query, &ctx)
+        }
+    };
+    let matched = match found {
+        Ok(Some(matched)) => matched,
+        other => {
+            remove_active_track(dl_state, id, track_idx);
+            staging::discard(music_dir, &staged);
+            if should_stop() {
+                job.cancelled.store(true, Ordering::Relaxed);
+                return;
+            }
+            let error = match other {
+                Err(e) => e,
+                _ => format!("Not found in {}", source.name()),
+            };
+            job.fail(track_idx, track_name, &error);
+            return;
+        }
+    };
+
+    // Check cancel between search and download
+    if should_stop() {
+        job.cancelled.store(true, Ordering::Relaxed);
+        remove_active_track(dl_state, id, track_idx);
+        staging::discard(music_dir, &staged);
+        return;
+    }
+
+    // Download
+    update_active_track_status(dl_state, id, track_idx, "downloading");
+    set_active_track_match(dl_state, id, track_idx, &matched);
+    emit_progress(
+        app,
+        dl_state,
+        id,
+        DownloadProgress {
+            artist: req.artist.clone(),
+            album: req.album.clone(),
+            track_index: track_idx,
+            total_tracks: job.total_tracks,
+            track_name: track_name.to_string(),
+            status: "downloading".into(),
+            matched: Some(matched.clone()),
+            ..Default::default()
+        },
+    );
+
+    let mut last_emit: Option<Instant> = None;
+    let mut last_progress = TrackProgress::default();
+    let result = source.fetch(&matched, &staged, &ctx, &mut |p| {
+        last_progress = p.clone();
+        update_track_progress(dl_state, id, track_idx, p);
+        // Progress lines arrive many times a second; pass on a few
+        let finished = p.percent.is_some_and(|pct| pct >= 100.0);
+        if finished || last_emit.map_or(true, |t| t.elapsed() >= PROGRESS_INTERVAL) {
+            last_emit = Some(Instant::now());
+            emit_progress(
+                app,
+                dl_state,
+                id,
+                DownloadProgress {
+                    artist: req.artist.clone(),
+                    album: req.album.clone(),
+                    track_index: track_idx,
+                    total_tracks: job.total_tracks,
+                    track_name: track_name.to_string(),
+                    status: "downloading".into(),
+                    progress: Some(p.clone()),
+                    ..Default::default()
+                },
+            );
+        }
+    });
+
+    let staged = match result {
+        Ok(written) => written,
+        Err(e) => {
+            remove_active_track(dl_state, id, track_idx);
+            staging::discard(music_dir, &staged);
+            if should_stop() {
+                job.cancelled.store(true, Ordering::Relaxed);
+                return;
+            }
+            job.fail(track_idx, track_name, &e);
+            return;
+        }
+    };
+    // Sources that don't convert keep the file's own format
+    let final_path = match staged.extension() {
+        Some(ext) => filepath.with_extension(ext),
+        None => filepath.to_path_buf(),
+    };
+    let filepath = final_path.as_path();
+    // The new file takes this path, so it isn't an old copy to delete
+    owned.retain(|old| old.path != filepath);
+    let bytes = last_progress.total_bytes.unwrap_or(last_progress.downloaded_bytes);
+    record_track_bytes(dl_state, id, track_idx, bytes);
+
+    // Check cancel between download and tagging
+    if should_stop() {
+        job.cancelled.store(true, Ordering::Relaxed);
+        remove_active_track(dl_state, id, track_idx);
+        staging::discard(music_dir, &staged);
+        return;
+    }
+
+    // Tag
+    update_active_track_status(dl_state, id, track_idx, "tagging");
+    job.emit(track_idx, track_name, "tagging", None);
+
+    let tags = TrackTags {
+        title: track_name,
+        artist: &req.artist,
+        album: &req.album,
+        album_artist: &req.artist,
+        year: &req.year,
+        track: track.track,
+        total_tracks: track.disc_tracks,
+        disc: track.disc,
+        total_discs: job.total_discs,
+        genre: &req.genre,
+        release_id: job.release_id,
+        release_group_id: req.release_group_id.as_deref(),
+        cover: job.cover,
+    };
+    if let Err(e) = tags::write_tags(&staged, &tags) {
+        log::warn!("Failed to tag {}: {e}", filepath.display());
+    }
+
+    if config.lyrics != LyricsMode::Off {
+        update_active_track_status(dl_state, id, track_idx, "fetching_lyrics");
+        job.emit(track_idx, track_name, "fetching_lyrics", None);
+    }
+    let lrc = lyrics::add(config, &staged, &tags);
+
+    if let Err(e) = staging::commit(music_dir, &staged, filepath) {
+        staging::discard(music_dir, &staged);
+        if let Some(lrc) = &lrc {
+            staging::discard(music_dir, lrc);
+        }
+        remove_active_track(dl_state, id, track_idx);
+        job.fail(track_idx, track_name, &e);
+        return;
+    }
+
+    commit_lyrics(music_dir, lrc, filepath);
+
+    // Swap the new file in for the old copies (only non-empty for `Replace`)
+    for old in &owned {
+        if let Err(e) = std::fs::remove_file(&old.path) {
+            log::warn!("Failed to remove {}: {e}", old.path.display());
+        }
+        let old_lrc = old.path.with_extension("lrc");
+        if old_lrc != filepath.with_extension("lrc") {
+            let _ = std::fs::remove_file(old_lrc);
+        }
+    }
+    if let Some(library) = dl_state.library.lock().unwrap().as_mut() {
+        for old in &owned {
+            library.remove_file(&old.path);
+        }
+        // Re-read in case the file overwrote one the index already had
+        library.remove_file(filepath);
+        library.add_file(filepath);
+    }
+
+    // Complete
+    let new_count = job.completed.fetch_add(1, Ordering::Relaxed) + 1;
+    remove_active_track(dl_state, id, track_idx);
+    record_track_done(dl_state, id, track_idx, track.disc, new_count);
+    set_track_result(dl_state, id, track_idx, "done", None);
+    job.emit(track_idx, track_name, "done", None);
+}
+
+// ────────────────────────────────────────────────────────────────────────────
+// Album download (concurrent tracks)
+// ────────────────────────────────────────────────────────────────────────────
+
+fn download_album(
+    app: &AppHandle,
+    dl_state: &DownloaderStateInner,
+    id: u64,
+    req: &AlbumRequest,
+) -> Result<(), String> {
+    let config = dl_state.config.lock().unwrap().clone();
+    config.validate()?;
+    let template = config.template()?;
+
+    // Pin the edition once so the cover and the tracklist agree
+    let release_id = resolve_release_id(req);
+
+    // Fetch cover
+    emit_track_progress(
+        app, dl_state, id, req, 0, 0, "", "fetching_cover", None,
+    );
+
+    let art = artwork::album_art(
+        &config,
+        release_id.as_deref().ok(),
+        req.release_group_id.as_deref(),
+        &req.artist,
+        &req.album,
+        config.cover_extras,
+    );
+    let cover_data = art.embedded(&config);
+
+    // Fetch tracklist
+    emit_track_progress(
+        app, dl_state, id, req, 0, 0, "", "fetching_tracklist", None,
+    );
+
+    let tracks = match &req.tracks {
+        Some(t) if !t.is_empty() => t.clone(),
+        _ => fetch_release_tracks(release_id.as_ref()?)?,
+    };
+    let total_tracks = tracks.len();
+    let total_discs = total_discs(&tracks);
+    let selected = |i: usize| req.only_tracks.as_ref().map_or(true, |only| only.contains(&i));
+    let skipped = (0..total_tracks).filter(|i| !selected(*i)).count();
+
+    // Directories are created as tracks are moved out of staging
+    let paths = album_track_paths(&config, &template, req, &tracks);
+    let mut album_dirs: Vec<PathBuf> = paths.iter().filter_map(|p| p.parent()).map(Path::to_path_buf).collect();
+    album_dirs.dedup();
+
+    {
+        let mut s = dl_state.state.lock().unwrap();
+        if let Some(album) = album_mut(&mut s, id) {
+            album.total_tracks = total_tracks;
+            album.completed_tracks = skipped;
+            album.discs = (1..=total_discs)
+                .map(|disc| DiscProgress {
+                    disc,
+                    // Tracks left out of a retry already finished
+                    completed_tracks: (0..total_tracks)
+                        .filter(|i| tracks[*i].disc == disc && !selected(*i))
+                        .count(),
+                    total_tracks: tracks.iter().filter(|t| t.disc == disc).count(),
+                })
+                .collect();
+            let previous = std::mem::take(&mut album.tracks);
+            album.tracks = tracks
+                .iter()
+                .enumerate()
+                .map(|(i, t)| match previous.get(i) {
+                    Some(prev) if !selected(i) => prev.clone(),
+                    _ => TrackState {
+                        track_index: i,
+                        title: t.title.clone(),
+                        disc: t.disc,
+                        track: t.track,
+                        status: "pending".into(),
+                        error: None,
+                    },
+                })
+                .collect();
+            dl_state.journal.set_total_tracks(id, total_tracks);
+        }
+    }
+
+    // Concurrent track downloads using scoped threads + crossbeam channel
+    let source = req.source.backend();
+    let job = AlbumJob {
+        app,
+        dl_state,
+        id,
+        req,
+        source: source.as_ref(),
+        config: &config,
+        total_tracks,
+        total_discs,
+        release_id: release_id.as_deref().ok(),
+        cover: cover_data.as_deref(),
+        completed: AtomicUsize::new(skipped),
+        cancelled: AtomicBool::new(false),
+    };
+
+    let (sender, receiver) = crossbeam_channel::bounded::<(usize, AlbumTrack, PathBuf)>(tracks.len());
+    for (i, (track, path)) in tracks.iter().zip(paths).enumerate() {
+        if selected(i) {
+            let _ = sender.send((i, track.clone(), path));
+        }
+    }
+    drop(sender);
+
+    let num_workers = config.concurrent_tracks.max(1).min(total_tracks - skipped);
+    let stagger = Duration::from_millis(config.track_stagger_ms);
+
+    let job = &job;
+
+    std::thread::scope(|scope| {
+        for worker_id in 0..num_workers {
+            let recv = receiver.clone();
+            // Stagger worker starts to avoid simultaneous YouTube searches
+            if worker_id > 0 {
+                std::thread::sleep(stagger);
+            }
+
+            scope.spawn(move || {
+                while let Ok((track_idx, track, filepath)) = recv.recv() {
+                    if job.cancelled.load(Ordering::Relaxed) || job.should_stop() {
+                        job.cancelled.store(true, Ordering::Relaxed);
+                        break;
+                    }
+
+                    process_single_track(job, track_idx, &track, &filepath);
+                }
+            });
+        }
+    });
+
+    if job.cancelled.load(Ordering::Relaxed) {
+        return Err("Cancelled".into());
+    }
+
+    // Discs can have folders of their own; each gets the cover
+    for dir in album_dirs.iter().filter(|d| d.is_dir()) {
+        art.save(&config, dir);
+    }
+
+    Ok(())
+}
+
+fn download_single_song(
+    app: &AppHandle,
+    dl_state: &DownloaderStateInner,
+    song: &SongRequest,
+    reference: &str,
+    source: &Source,
+    id: u64,
+) -> Result<(), String> {
+    let config = &dl_state.config.lock().unwrap().clone();
+    config.validate()?;
+
+    let album = if song.album.is_empty() { "Singles" } else { &song.album };
+    let track_num = song.track_num.unwrap_or(1);
+
+    let fields = TrackFields {
+        artist: &song.artist,
+        album_artist: &song.artist,
+        album,
+        year: &song.year,
+        genre: &song.genre,
+        title: &song.title,
+        track: track_num,
+        total_tracks: 1,
+        disc: 1,
+        total_discs: 1,
+        ext: config.audio_format.extension(),
+    };
+    let music_dir = Path::new(&config.music_dir);
+    let mut filepath = music_dir.join(config.template()?.render(&fields));
+
+    // Skip if exists, in whatever format the source delivered it last time
+    if library::existing_audio(&filepath).is_some() {
+        emit_progress(
+            app,
+            dl_state,
+            id,
+            DownloadProgress {
+                artist: song.artist.clone(),
+                album: song.title.clone(),
+                total_tracks: 1,
+                track_name: song.title.clone(),
+                status: "done".into(),
+                ..Default::default()
+            },
+        );
+        return Ok(());
+    }
+
+    let staged = staging::staged_path(music_dir, &filepath);
+    staging::prepare(music_dir, &staged)?;
+
+    let source = source.backend();
+    let should_stop = || stop_requested(dl_state, id);
+    let ctx = FetchContext {
+        config,
+        should_stop: &should_stop,
+    };
+    let matched = match source.resolve(reference, &ctx) {
+        Ok(matched) => matched,
+        Err(e) => {
+            staging::discard(music_dir, &staged);
+            return Err(e);
+        }
+    };
+
+    let mut last_emit: Option<Instant> = None;
+    let fetched = source.fetch(&matched, &staged, &ctx, &mut |p| {
+        if last_emit.is_some_and(|t| t.elapsed() < PROGRESS_INTERVAL) {
+            return;
+        }
+        last_emit = Some(Instant::now());
+        emit_progress(
+            app,
+            dl_state,
+            id,
+            DownloadProgress {
+                artist: song.artist.clone(),
+                album: song.title.clone(),
+                total_tracks: 1,
+                track_name: song.title.clone(),
+                status: "downloading".into(),
+                progress: Some(p.clone()),
+                ..Default::default()
+            },
+        );
+    });
+    let staged = match fetched {
+        Ok(written) => written,
+        Err(e) => {
+            staging::discard(music_dir, &staged);
+            return Err(e);
+        }
+    };
+    if let Some(ext) = staged.extension() {
+        filepath.set_extension(ext);
+    }
+
+    let cover_data = if !song.album.is_empty() {
+        artwork::album_art(config, None, None, &song.artist, &song.album, false).embedded(config)
+    } else {
+        None
+    };
+
+    emit_progress(
+        app,
+        dl_state,
+        id,
+        DownloadProgress {
+            artist: song.artist.clone(),
+            album: song.title.clone(),
+            total_tracks: 1,
+            track_name: song.title.clone(),
+            status: "tagging".into(),
+            ..Default::default()
+        },
+    );
+
+    let tags = TrackTags {
+        title: &song.title,
+        artist: &song.artist,
+        album,
+        album_artist: &song.artist,
+        year: &song.year,
+        track: track_num,
+        total_tracks: 1,
+        disc: 1,
+        total_discs: 1,
+        genre: if song.genre.is_empty() { "Rock" } else { &song.genre },
+        release_id: None,
+        release_group_id: None,
+        cover: cover_data.as_deref(),
+    };
+    if let Err(e) = tags::write_tags(&staged, &tags) {
+        log::warn!("Failed to tag {}: {e}", filepath.display());
+    }
+    let lrc = lyrics::add(config, &staged, &tags);
+    if let Err(e) = staging::commit(music_dir, &staged, &filepath) {
+        staging::discard(music_dir, &staged);
+        if let Some(lrc) = &lrc {
+            staging::discard(music_dir, lrc);
+        }
+        return Err(e);
+    }
+    commit_lyrics(music_dir, lrc, &filepath);
+
+    emit_progress(
+        app,
+        dl_state,
+        id,
+        DownloadProgress {
+            artist: song.artist.clone(),
+            album: song.title.clone(),
+            total_tracks: 1,
+            track_name: song.title.clone(),
+            status: "done".into(),
+            ..Default::default()
+        },
+    );
+
+    Ok(())
+}
+
+fn total_discs(tracks: &[AlbumTrack]) -> usize {
+    tracks.iter().map(|t| t.disc).max().unwrap_or(1)
+}
+
+/// The requested tracks, or the album's MusicBrainz tracklist if none were given.
+fn album_tracklist(req: &AlbumRequest) -> Result<Vec<AlbumTrack>, String> {
+    match &req.tracks {
+        Some(t) if !t.is_empty() => Ok(t.clone()),
+        _ => fetch_release_tracks(&resolve_release_id(req)?),
+    }
+}
+
+/// The MusicBrainz release to take tracks and cover from: the chosen edition,
+/// else the default edition of the release group, else a text search for
+/// requests that carry no IDs (manual entries).
+fn resolve_release_id(req: &AlbumRequest) -> Result<String, String> {
+    if let Some(id) = &req.release_id {
+        return Ok(id.clone());
+    }
+
+    let release_id = match &req.release_group_id {
+        Some(rg_id) => fetch_group_releases(rg_id)?
+            .into_iter()
+            .next()
+            .map(|r| r.id)
+            .ok_or("Release group has no releases on MusicBrainz")?,
+        None => {
+            let query_str = format!("release:{} AND artist:{}", req.album, req.artist);
+            let encoded = urlencoding::encode(&query_str);
+            let url = format!(
+                "release/?query={encoded}&fmt=json&limit=1"
+            );
+            let data = mb_get(&url)?;
+
+            data["releases"]
+                .as_array()
+                .and_then(|r| r.first())
+                .and_then(|r| r["id"].as_str())
+                .ok_or("No release found on MusicBrainz")?
+                .to_string()
+        }
+    };
+
+    Ok(release_id)
+}
+
+/// All releases in a group, official ones first, then oldest first.
+fn fetch_group_releases(release_group_id: &str) -> Result<Vec<MbRelease>, String> {
+    let url = format!(
+        "release/?release-group={release_group_id}&inc=media&fmt=json&limit=100"
+    );
+    let data = mb_get(&url)?;
+
+    let releases = data["releases"]
+        .as_array()
+        .ok_or("No releases in response")?;
+
+    let mut results: Vec<MbRelease> = releases
+        .iter()
+        .filter_map(|r| {
+            let media = r["media"].as_array().map(Vec::as_slice).unwrap_or_default();
+            let mut formats: Vec<String> = Vec::new();
+            for medium in media {
+                let format = medium["format"].as_str().unwrap_or("Unknown").to_string();
+                if !formats.contains(&format) {
+                    formats.push(format);
+                }
+            }
+            Some(MbRelease {
+                id: r["id"].as_str()?.to_string(),
+                title: r["title"].as_str().unwrap_or("").to_string(),
+                disambiguation: r["disambiguation"].as_str().unwrap_or("").to_string(),
+                status: r["status"].as_str().unwrap_or("").to_string(),
+                date: r["date"].as_str().unwrap_or("").to_string(),
+                country: r["country"].as_str().unwrap_or("").to_string(),
+                format: formats.join(" + "),
+                disc_count: media.len(),
+                track_count: media
+                    .iter()
+                    .filter_map(|m| m["track-count"].as_u64())
+                    .sum::<u64>() as usize,
+            })
+        })
+        .collect();
+
+    // Undated releases sort last
+    results.sort_by(|a, b| {
+        (a.status != "Official")
+            .cmp(&(b.status != "Official"))
+            .then_with(|| (a.date.is_empty(), &a.date).cmp(&(b.date.is_empty(), &b.date)))
+    });
+
+    Ok(results)
+}
+
+fn fetch_release_tracks(release_id: &str) -> Result<Vec<AlbumTrack>, String> {
+    let url2 = format!(
+        "release/{release_id}?inc=recordings&fmt=json"
+    );
+    let data2 = mb_get(&url2)?;
+
+    let mut tracks = Vec::new();
+    if let Some(media) = data2["media"].as_array() {
+        for (i, medium) in media.iter().enumerate() {
+            let disc = medium["position"].as_u64().map_or(i + 1, |p| p as usize);
+            if let Some(medium_tracks) = medium["tracks"].as_array() {
+                let entries: Vec<(&str, Option<u64>)> = medium_tracks
+                    .iter()
+                    .filter_map(|t| {
+                        let length = t["length"].as_u64().or_else(|| t["recording"]["length"].as_u64());
+                        Some((t["title"].as_str()?, length))
+                    })
+                    .collect();
+                for (n, (title, length_ms)) in entries.iter().enumerate() {
+                    tracks.push(AlbumTrack {
+                        title: title.to_string(),
+                        disc,
+                        track: n + 1,
+                        disc_tracks: entries.len(),
+                        length_ms: *length_ms,
+                        source_ref: None,
+                    });
+                }
+            }
+        }
+    }
+
+    if tracks.is_empty() {
+        return Err("No tracks found on MusicBrainz".into());
+    }
+
+    Ok(tracks)
+}
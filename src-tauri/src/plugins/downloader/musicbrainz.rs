@@ -0,0 +1,240 @@
+//! Shared MusicBrainz web service client.
+//!
+//! All lookups go through one client so they share a connection pool and a
+//! token-bucket limiter that keeps the whole app within MusicBrainz's one
+//! request per second, however many threads are asking. 503 and 429 replies
+//! are retried with backoff. Successful responses are cached on disk for
+//! `musicbrainz_cache_hours`, so browsing the same discography again does not
+//! touch the network.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use super::{DownloaderConfig, MB_USER_AGENT};
+
+pub const DEFAULT_BASE_URL: &str = "https://musicbrainz.org/ws/2";
+
+const CACHE_FILE: &str = "musicbrainz_cache.json";
+const MAX_CACHE_ENTRIES: usize = 2000;
+
+/// Time to earn one request token
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+/// Tokens that can accumulate while idle
+const BURST: f64 = 1.0;
+
+const MAX_RETRIES: u32 = 4;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Unix time in seconds
+    fetched_at: u64,
+    body: serde_json::Value,
+}
+
+struct Settings {
+    base_url: String,
+    ttl: Duration,
+}
+
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+struct Cache {
+    path: Option<PathBuf>,
+    entries: HashMap<String, CacheEntry>,
+}
+
+pub struct MbClient {
+    http: reqwest::blocking::Client,
+    settings: Mutex<Settings>,
+    bucket: Mutex<Bucket>,
+    cache: Mutex<Cache>,
+}
+
+/// The process-wide client.
+pub fn client() -> &'static MbClient {
+    static CLIENT: OnceLock<MbClient> = OnceLock::new();
+    CLIENT.get_or_init(|| MbClient {
+        http: reqwest::blocking::Client::builder()
+            .user_agent(MB_USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default(),
+        settings: Mutex::new(Settings {
+            base_url: DEFAULT_BASE_URL.into(),
+            ttl: Duration::from_secs(24 * 3600),
+        }),
+        bucket: Mutex::new(Bucket {
+            tokens: BURST,
+            last: Instant::now(),
+        }),
+        cache: Mutex::new(Cache {
+            path: None,
+            entries: HashMap::new(),
+        }),
+    })
+}
+
+/// Point the client at the app's cache file and apply `config`.
+pub fn init(app: &AppHandle, config: &DownloaderConfig) {
+    let client = client();
+    client.configure(config);
+
+    let path = app.path().app_cache_dir().ok().map(|dir| dir.join(CACHE_FILE));
+    let entries = path
+        .as_deref()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    *client.cache.lock().unwrap() = Cache { path, entries };
+}
+
+impl MbClient {
+    pub fn configure(&self, config: &DownloaderConfig) {
+        let mut settings = self.settings.lock().unwrap();
+        settings.base_url = config.musicbrainz_url.trim_end_matches('/').to_string();
+        settings.ttl = Duration::from_secs(config.musicbrainz_cache_hours * 3600);
+    }
+
+    /// GET `path` (e.g. `artist/?query=...&fmt=json`) relative to the base URL.
+    pub fn get(&self, path: &str) -> Result<serde_json::Value, String> {
+        let (url, ttl) = {
+            let settings = self.settings.lock().unwrap();
+            let url = format!("{}/{}", settings.base_url, path.trim_start_matches('/'));
+            (url, settings.ttl)
+        };
+
+        if let Some(body) = self.cached(&url, ttl) {
+            return Ok(body);
+        }
+
+        let mut backoff = INITIAL_BACKOFF;
+        for attempt in 0..=MAX_RETRIES {
+            self.acquire();
+            let resp = self
+                .http
+                .get(&url)
+                .send()
+                .map_err(|e| format!("MusicBrainz request failed: {e}"))?;
+
+            let status = resp.status();
+            if status.as_u16() == 503 || status.as_u16() == 429 {
+                if attempt == MAX_RETRIES {
+                    break;
+                }
+                let wait = retry_after(&resp).unwrap_or(backoff);
+                log::warn!("MusicBrainz answered {status}, retrying in {}s", wait.as_secs());
+                std::thread::sleep(wait);
+                backoff *= 2;
+                continue;
+            }
+            if !status.is_success() {
+                return Err(format!("MusicBrainz returned {status}"));
+            }
+
+            let body = resp
+                .text()
+                .map_err(|e| format!("Failed to read response: {e}"))?;
+            let value: serde_json::Value =
+                serde_json::from_str(&body).map_err(|e| format!("Failed to parse JSON: {e}"))?;
+            if !ttl.is_zero() {
+                self.store(url, value.clone());
+            }
+            return Ok(value);
+        }
+
+        Err("MusicBrainz is rate limiting requests, try again later".into())
+    }
+
+    /// Block until a request token is available.
+    fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().unwrap();
+                let now = Instant::now();
+                let earned = (now - bucket.last).as_secs_f64() / REQUEST_INTERVAL.as_secs_f64();
+                bucket.tokens = (bucket.tokens + earned).min(BURST);
+                bucket.last = now;
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+                REQUEST_INTERVAL.mul_f64(1.0 - bucket.tokens)
+            };
+            std::thread::sleep(wait);
+        }
+    }
+
+    fn cached(&self, url: &str, ttl: Duration) -> Option<serde_json::Value> {
+        let cache = self.cache.lock().unwrap();
+        let entry = cache.entries.get(url)?;
+        (now_secs().saturating_sub(entry.fetched_at) < ttl.as_secs()).then(|| entry.body.clone())
+    }
+
+    fn store(&self, url: String, body: serde_json::Value) {
+        let mut cache = self.cache.lock().unwrap();
+        cache.entries.insert(
+            url,
+            CacheEntry {
+                fetched_at: now_secs(),
+                body,
+            },
+        );
+
+        if cache.entries.len() > MAX_CACHE_ENTRIES {
+            let mut by_age: Vec<(String, u64)> = cache
+                .entries
+                .iter()
+                .map(|(url, e)| (url.clone(), e.fetched_at))
+                .collect();
+            by_age.sort_by_key(|(_, fetched_at)| *fetched_at);
+            let excess = cache.entries.len() - MAX_CACHE_ENTRIES;
+            for (url, _) in by_age.into_iter().take(excess) {
+                cache.entries.remove(&url);
+            }
+        }
+
+        if let Some(path) = &cache.path {
+            if let Err(e) = write_atomic(path, &cache.entries) {
+                log::warn!("Failed to write MusicBrainz cache: {e}");
+            }
+        }
+    }
+}
+
+/// `Retry-After` in seconds, as MusicBrainz sends it.
+fn retry_after(resp: &reqwest::blocking::Response) -> Option<Duration> {
+    resp.headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn write_atomic(path: &Path, entries: &HashMap<String, CacheEntry>) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string(entries).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
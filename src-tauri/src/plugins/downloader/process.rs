@@ -0,0 +1,101 @@
+//! Supervised yt-dlp runs.
+//!
+//! Children run in their own process group, so stopping one also takes down
+//! the ffmpeg it spawned (on Windows, `taskkill /T` walks the tree instead).
+//! The supervisor polls a stop callback while it reads the child's output and
+//! kills the tree as soon as it fires, or when the child has printed nothing
+//! for the stall timeout.
+
+use std::io::{self, BufRead, BufReader};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+/// How often the stop callback is checked.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How a supervised run ended.
+#[derive(Debug)]
+pub enum Exit {
+    Finished(ExitStatus),
+    /// The stop callback fired
+    Stopped,
+    /// No output for longer than the stall timeout
+    Stalled,
+}
+
+/// Run `cmd`, passing each stdout line to `on_line`.
+///
+/// A zero `stall_timeout` disables the timeout. Stderr is discarded.
+pub fn run(
+    mut cmd: Command,
+    stall_timeout: Duration,
+    should_stop: impl Fn() -> bool,
+    mut on_line: impl FnMut(&str),
+) -> io::Result<Exit> {
+    cmd.stdout(Stdio::piped()).stderr(Stdio::null());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    let mut child = cmd.spawn()?;
+
+    // Read on a helper thread so the supervisor can time out a silent child
+    let (tx, rx) = crossbeam_channel::unbounded::<String>();
+    if let Some(stdout) = child.stdout.take() {
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    let mut last_output = Instant::now();
+    loop {
+        if should_stop() {
+            kill_tree(&mut child);
+            return Ok(Exit::Stopped);
+        }
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(line) => {
+                last_output = Instant::now();
+                on_line(&line);
+            }
+            // Stdout closed: the child is exiting
+            Err(crossbeam_channel::RecvTimeoutError::Disconnected) => break,
+            Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
+                if !stall_timeout.is_zero() && last_output.elapsed() >= stall_timeout {
+                    log::warn!("Killing yt-dlp after {}s without output", stall_timeout.as_secs());
+                    kill_tree(&mut child);
+                    return Ok(Exit::Stalled);
+                }
+            }
+        }
+    }
+
+    child.wait().map(Exit::Finished)
+}
+
+/// Kill the child and everything it started, then reap it.
+fn kill_tree(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        // The child leads its own group, so its pid is the group id
+        let pgid = child.id() as libc::pid_t;
+        unsafe {
+            libc::kill(-pgid, libc::SIGKILL);
+        }
+    }
+    #[cfg(windows)]
+    {
+        let _ = Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &child.id().to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
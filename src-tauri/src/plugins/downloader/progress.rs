@@ -0,0 +1,57 @@
+//! Live download progress from yt-dlp.
+//!
+//! yt-dlp is run with `--newline` and a progress template that prints one
+//! machine-readable line per update; everything else on stdout is ignored.
+//! Fields yt-dlp doesn't know yet come through as `NA`.
+
+use serde::{Deserialize, Serialize};
+
+const PREFIX: &str = "[lumina-progress]";
+
+/// yt-dlp arguments that make it print lines [`parse_line`] understands.
+pub fn ytdlp_args() -> [&'static str; 4] {
+    [
+        "--newline",
+        "--progress-template",
+        concat!(
+            "download:[lumina-progress] ",
+            "%(progress.downloaded_bytes)s %(progress.total_bytes)s ",
+            "%(progress.total_bytes_estimate)s %(progress.speed)s %(progress.eta)s"
+        ),
+        "--progress",
+    ]
+}
+
+/// Progress of one track's download.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrackProgress {
+    pub downloaded_bytes: u64,
+    /// Exact size, or yt-dlp's estimate when the size isn't known up front
+    pub total_bytes: Option<u64>,
+    /// 0-100
+    pub percent: Option<f64>,
+    /// Bytes per second
+    pub speed: Option<f64>,
+    pub eta_secs: Option<u64>,
+}
+
+/// Parse a progress line, or `None` for any other output.
+pub fn parse_line(line: &str) -> Option<TrackProgress> {
+    let mut fields = line.trim().strip_prefix(PREFIX)?.split_whitespace();
+    let mut next = || fields.next().and_then(|v| v.parse::<f64>().ok());
+
+    let downloaded = next()?;
+    let total = next();
+    let estimate = next();
+    let speed = next();
+    let eta = next();
+
+    let total_bytes = total.or(estimate).filter(|t| *t > 0.0).map(|t| t as u64);
+    Some(TrackProgress {
+        downloaded_bytes: downloaded as u64,
+        total_bytes,
+        percent: total_bytes.map(|t| (downloaded / t as f64 * 100.0).min(100.0)),
+        speed,
+        eta_secs: eta.map(|e| e as u64),
+    })
+}
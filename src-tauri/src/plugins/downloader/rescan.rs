@@ -0,0 +1,221 @@
+//! Library rescans on the Subsonic server (Navidrome) after files land.
+//!
+//! The frontend registers the server it is connected to. Finished downloads
+//! and imports then ask for a scan, which starts once nothing has asked for
+//! one during the configured delay, so a burst of completions causes a single
+//! scan. The scan is followed with `getScanStatus` until the server reports it
+//! done, emitting `downloader:scan-progress` along the way and
+//! `downloader:scan-complete` (or `downloader:scan-failed`) at the end.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+const API_VERSION: &str = "1.16.1";
+const CLIENT_NAME: &str = "Lumina";
+
+/// How often a running scan is checked on.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Stop following a scan that hasn't finished in this long.
+const SCAN_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// A Subsonic server and its login.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanServer {
+    pub url: String,
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ScanProgress {
+    /// Items the server reports so far
+    pub count: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ScanComplete {
+    /// Items the server reports once the scan is done
+    pub count: u64,
+    /// How much `count` grew over the scan, i.e. the items it added
+    pub new_items: u64,
+}
+
+#[derive(Default)]
+struct Pending {
+    /// When the batched scan starts; `None` when none is asked for
+    due: Option<Instant>,
+    /// Whether a thread is waiting for `due` or running a scan
+    running: bool,
+}
+
+#[derive(Default)]
+pub struct Rescanner {
+    /// `None` until the frontend connects to a server
+    server: Mutex<Option<ScanServer>>,
+    pending: Mutex<Pending>,
+}
+
+impl Rescanner {
+    pub fn set_server(&self, server: Option<ScanServer>) {
+        *self.server.lock().unwrap() = server;
+    }
+
+    /// Scan after `delay`, unless another request comes first, which moves
+    /// the scan back again. A request made during a scan runs another one
+    /// afterwards, so files written meanwhile aren't missed.
+    pub fn request(self: &Arc<Self>, app: &AppHandle, delay: Duration) {
+        if self.server.lock().unwrap().is_none() {
+            log::info!("No Subsonic server connected, skipping rescan");
+            return;
+        }
+        let mut pending = self.pending.lock().unwrap();
+        pending.due = Some(Instant::now() + delay);
+        if pending.running {
+            return;
+        }
+        pending.running = true;
+        let this = Arc::clone(self);
+        let app = app.clone();
+        std::thread::spawn(move || this.run(&app));
+    }
+
+    fn run(&self, app: &AppHandle) {
+        loop {
+            let wait = {
+                let mut pending = self.pending.lock().unwrap();
+                match pending.due {
+                    None => {
+                        pending.running = false;
+                        return;
+                    }
+                    Some(due) => {
+                        let wait = due.saturating_duration_since(Instant::now());
+                        if wait.is_zero() {
+                            pending.due = None;
+                        }
+                        wait
+                    }
+                }
+            };
+            if !wait.is_zero() {
+                std::thread::sleep(wait);
+                continue;
+            }
+
+            let Some(server) = self.server.lock().unwrap().clone() else {
+                continue;
+            };
+            match scan(app, &server) {
+                Ok(done) => {
+                    log::info!(
+                        "Library rescan finished: {} items, {} new",
+                        done.count,
+                        done.new_items
+                    );
+                    let _ = app.emit("downloader:scan-complete", done);
+                }
+                Err(e) => {
+                    log::warn!("Library rescan failed: {e}");
+                    let _ = app.emit("downloader:scan-failed", serde_json::json!({ "error": e }));
+                }
+            }
+        }
+    }
+}
+
+/// State of the server's scanner, as `startScan` and `getScanStatus` report it.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanStatus {
+    scanning: bool,
+    #[serde(default)]
+    count: u64,
+}
+
+/// Start a scan and follow it to the end.
+fn scan(app: &AppHandle, server: &ScanServer) -> Result<ScanComplete, String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    // Let an unfinished scan run out first, so its items aren't counted as new
+    let started = Instant::now();
+    let mut before = call(&client, server, "getScanStatus")?;
+    while before.scanning {
+        wait(started)?;
+        before = call(&client, server, "getScanStatus")?;
+    }
+
+    let mut status = call(&client, server, "startScan")?;
+    log::info!("Library rescan started on {}", server.url);
+    let started = Instant::now();
+    while status.scanning {
+        let _ = app.emit(
+            "downloader:scan-progress",
+            ScanProgress {
+                count: status.count,
+            },
+        );
+        wait(started)?;
+        status = call(&client, server, "getScanStatus")?;
+    }
+    Ok(ScanComplete {
+        count: status.count,
+        new_items: status.count.saturating_sub(before.count),
+    })
+}
+
+fn wait(started: Instant) -> Result<(), String> {
+    if started.elapsed() > SCAN_TIMEOUT {
+        return Err("Timed out waiting for the scan to finish".into());
+    }
+    std::thread::sleep(POLL_INTERVAL);
+    Ok(())
+}
+
+/// Call a Subsonic `endpoint` that answers with a `scanStatus`.
+fn call(
+    client: &reqwest::blocking::Client,
+    server: &ScanServer,
+    endpoint: &str,
+) -> Result<ScanStatus, String> {
+    // Hex-encoded, which every Subsonic server accepts, so the password
+    // never shows up in the clear in server logs
+    let password = format!("enc:{}", hex(server.password.as_bytes()));
+    let query = [
+        ("u", server.username.as_str()),
+        ("p", password.as_str()),
+        ("v", API_VERSION),
+        ("c", CLIENT_NAME),
+        ("f", "json"),
+    ];
+    let base = server.url.trim_end_matches('/');
+    let resp = client
+        .get(format!("{base}/rest/{endpoint}"))
+        .query(&query)
+        .send()
+        .map_err(|e| format!("{endpoint} failed: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!("{endpoint} returned {}", resp.status()));
+    }
+    let body = resp.text().map_err(|e| e.to_string())?;
+    let data: serde_json::Value = serde_json::from_str(&body)
+        .map_err(|e| format!("Failed to parse {endpoint} response: {e}"))?;
+
+    let data = &data["subsonic-response"];
+    if data["status"] != "ok" {
+        let message = data["error"]["message"].as_str().unwrap_or("unknown error");
+        return Err(format!("{endpoint} failed: {message}"));
+    }
+    serde_json::from_value(data["scanStatus"].clone())
+        .map_err(|e| format!("Failed to parse {endpoint} response: {e}"))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,48 @@
+//! Daily time window the download queue is allowed to run in.
+//!
+//! Times are local wall-clock `HH:MM`. A window whose end is before its start
+//! runs past midnight (`23:00`-`07:00`), and equal ends mean all day. Only the
+//! start of an item is gated: an album that is running when the window closes
+//! finishes, and the next one waits for the window to open again.
+
+use chrono::{Local, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeWindow {
+    /// `HH:MM`
+    pub start: String,
+    /// `HH:MM`
+    pub end: String,
+}
+
+fn parse_time(s: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(s.trim(), "%H:%M").map_err(|_| format!("Invalid time {s:?}: use HH:MM"))
+}
+
+impl TimeWindow {
+    pub fn validate(&self) -> Result<(), String> {
+        parse_time(&self.start)?;
+        parse_time(&self.end)?;
+        Ok(())
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        let (Ok(start), Ok(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return true;
+        };
+        // Compare whole minutes so the end minute itself is outside
+        let time = NaiveTime::from_hms_opt(time.hour(), time.minute(), 0).unwrap_or(time);
+        if start < end {
+            start <= time && time < end
+        } else if start > end {
+            time >= start || time < end
+        } else {
+            true
+        }
+    }
+
+    pub fn is_open_now(&self) -> bool {
+        self.contains(Local::now().time())
+    }
+}
@@ -0,0 +1,176 @@
+//! Direct links to audio files, saved as they are.
+//!
+//! There is nothing to search, so every track needs its own URL. The format
+//! comes from the URL's extension, else from the `Content-Type`.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use reqwest::Url;
+
+use super::super::matching::TrackMatch;
+use super::super::progress::TrackProgress;
+use super::{audio_extension, DownloadSource, FetchContext, TrackQuery};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+const PROGRESS_EVERY: Duration = Duration::from_millis(250);
+
+pub struct HttpSource;
+
+impl DownloadSource for HttpSource {
+    fn name(&self) -> &'static str {
+        "direct link"
+    }
+
+    fn search(
+        &self,
+        _query: &TrackQuery,
+        _ctx: &FetchContext,
+    ) -> Result<Option<TrackMatch>, String> {
+        Err("Direct links can't be searched; give each track a URL".into())
+    }
+
+    fn resolve(&self, reference: &str, _ctx: &FetchContext) -> Result<TrackMatch, String> {
+        let url =
+            Url::parse(reference.trim()).map_err(|e| format!("Invalid URL {reference:?}: {e}"))?;
+        if url.scheme() != "http" && url.scheme() != "https" {
+            return Err(format!("Only http(s) links can be downloaded: {reference}"));
+        }
+        let file_name = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                urlencoding::decode(name)
+                    .map(|n| n.into_owned())
+                    .unwrap_or_else(|_| name.to_string())
+            })
+            .unwrap_or_else(|| url.to_string());
+        Ok(TrackMatch {
+            id: url.to_string(),
+            title: file_name,
+            channel: url.host_str().unwrap_or_default().to_string(),
+            duration: None,
+            score: 0.0,
+        })
+    }
+
+    fn fetch(
+        &self,
+        matched: &TrackMatch,
+        dest: &Path,
+        ctx: &FetchContext,
+        on_progress: &mut dyn FnMut(&TrackProgress),
+    ) -> Result<PathBuf, String> {
+        let stall_timeout = ctx.config.stall_timeout();
+        let client = reqwest::blocking::Client::builder()
+            // Applies to every read, so it catches stalls without capping the total
+            .timeout((!stall_timeout.is_zero()).then_some(stall_timeout))
+            .build()
+            .map_err(|e| e.to_string())?;
+        let mut resp = client
+            .get(&matched.id)
+            .send()
+            .map_err(|e| format!("Download failed: {e}"))?;
+        if !resp.status().is_success() {
+            return Err(format!(
+                "Download failed: server returned {}",
+                resp.status()
+            ));
+        }
+
+        let ext = audio_extension(resp.url().path())
+            .or_else(|| {
+                let content_type = resp
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)?
+                    .to_str()
+                    .ok()?;
+                extension_for_mime(content_type).map(str::to_string)
+            })
+            .ok_or("Link doesn't point to a supported audio file")?;
+        let path = dest.with_extension(ext);
+
+        let result = copy_body(&mut resp, &path, ctx, on_progress);
+        if result.is_err() {
+            let _ = std::fs::remove_file(&path);
+        }
+        result.map(|_| path)
+    }
+}
+
+/// Stream the response into `path`, honouring the stop check and the rate limit.
+fn copy_body(
+    resp: &mut reqwest::blocking::Response,
+    path: &Path,
+    ctx: &FetchContext,
+    on_progress: &mut dyn FnMut(&TrackProgress),
+) -> Result<(), String> {
+    let mut file =
+        std::fs::File::create(path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let total_bytes = resp.content_length();
+    let limit = ctx
+        .config
+        .rate_limit_per_download()
+        .map(|kib| kib as f64 * 1024.0);
+    let start = Instant::now();
+    let mut last_report: Option<Instant> = None;
+    let mut downloaded = 0u64;
+    let mut buf = vec![0u8; CHUNK_SIZE];
+
+    loop {
+        if (ctx.should_stop)() {
+            return Err("Cancelled".into());
+        }
+        let n = resp
+            .read(&mut buf)
+            .map_err(|e| format!("Download failed: {e}"))?;
+        if n == 0 {
+            break;
+        }
+        file.write_all(&buf[..n])
+            .map_err(|e| format!("Failed to write file: {e}"))?;
+        downloaded += n as u64;
+
+        if let Some(limit) = limit {
+            let due = Duration::from_secs_f64(downloaded as f64 / limit);
+            if let Some(ahead) = due.checked_sub(start.elapsed()) {
+                std::thread::sleep(ahead);
+            }
+        }
+
+        let done = total_bytes.is_some_and(|t| downloaded >= t);
+        if done || last_report.map_or(true, |t| t.elapsed() >= PROGRESS_EVERY) {
+            last_report = Some(Instant::now());
+            let speed = downloaded as f64 / start.elapsed().as_secs_f64().max(0.001);
+            on_progress(&TrackProgress {
+                downloaded_bytes: downloaded,
+                total_bytes,
+                percent: total_bytes.map(|t| (downloaded as f64 / t as f64 * 100.0).min(100.0)),
+                speed: Some(speed),
+                eta_secs: total_bytes.map(|t| (t.saturating_sub(downloaded) as f64 / speed) as u64),
+            });
+        }
+    }
+
+    if total_bytes.is_some_and(|t| downloaded < t) {
+        return Err("Download ended early".into());
+    }
+    file.flush()
+        .map_err(|e| format!("Failed to write file: {e}"))
+}
+
+fn extension_for_mime(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next()?.trim().to_ascii_lowercase();
+    Some(match mime.as_str() {
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/flac" | "audio/x-flac" => "flac",
+        "audio/ogg" | "audio/vorbis" => "ogg",
+        "audio/opus" => "opus",
+        "audio/mp4" | "audio/x-m4a" | "audio/m4a" => "m4a",
+        "audio/aac" => "aac",
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        _ => return None,
+    })
+}
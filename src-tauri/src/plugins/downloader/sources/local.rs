@@ -0,0 +1,204 @@
+//! Audio files already on disk, in a folder or a `.zip` archive.
+//!
+//! Tracks are found by file name: a file whose name, less any leading track
+//! number and artist, is the track title wins over one that merely contains
+//! it. Files are copied (or extracted) as they are; the originals stay put.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use super::super::matching::{normalize, TrackMatch};
+use super::super::progress::TrackProgress;
+use super::{audio_extension, DownloadSource, FetchContext, TrackQuery};
+
+pub struct LocalSource {
+    root: PathBuf,
+    is_zip: bool,
+    /// Audio files relative to the folder, or entry names in the archive
+    entries: Result<Vec<String>, String>,
+}
+
+impl LocalSource {
+    pub fn new(root: &Path) -> Self {
+        let is_zip = root
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("zip"));
+        let entries = if is_zip {
+            list_zip(root)
+        } else {
+            list_folder(root)
+        };
+        Self {
+            root: root.to_path_buf(),
+            is_zip,
+            entries,
+        }
+    }
+
+    fn entries(&self) -> Result<&[String], String> {
+        self.entries.as_deref().map_err(|e| e.clone())
+    }
+
+    fn to_match(&self, entry: &str, score: f64) -> TrackMatch {
+        TrackMatch {
+            id: entry.to_string(),
+            title: file_stem(entry).to_string(),
+            channel: self
+                .root
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            duration: None,
+            score,
+        }
+    }
+}
+
+impl DownloadSource for LocalSource {
+    fn name(&self) -> &'static str {
+        if self.is_zip {
+            "the archive"
+        } else {
+            "the folder"
+        }
+    }
+
+    fn search(
+        &self,
+        query: &TrackQuery,
+        _ctx: &FetchContext,
+    ) -> Result<Option<TrackMatch>, String> {
+        let title = normalize(query.title);
+        let artist = normalize(query.artist);
+        let best = self
+            .entries()?
+            .iter()
+            .filter_map(|entry| {
+                let name = clean_name(file_stem(entry), &artist);
+                let score = if name == title {
+                    100.0
+                } else if format!(" {name} ").contains(&format!(" {title} ")) {
+                    // Prefer the closest name among partial matches
+                    50.0 - (name.len() - title.len()) as f64 * 0.1
+                } else {
+                    return None;
+                };
+                Some((entry, score))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        Ok(best.map(|(entry, score)| self.to_match(entry, score)))
+    }
+
+    fn resolve(&self, reference: &str, _ctx: &FetchContext) -> Result<TrackMatch, String> {
+        let entry = self
+            .entries()?
+            .iter()
+            .find(|e| *e == reference)
+            .ok_or_else(|| format!("{reference} is not in {}", self.root.display()))?;
+        Ok(self.to_match(entry, 0.0))
+    }
+
+    fn fetch(
+        &self,
+        matched: &TrackMatch,
+        dest: &Path,
+        ctx: &FetchContext,
+        on_progress: &mut dyn FnMut(&TrackProgress),
+    ) -> Result<PathBuf, String> {
+        if (ctx.should_stop)() {
+            return Err("Cancelled".into());
+        }
+        let ext = audio_extension(&matched.id).ok_or("Not an audio file")?;
+        let path = dest.with_extension(ext);
+
+        let copied = if self.is_zip {
+            extract(&self.root, &matched.id, &path)
+        } else {
+            std::fs::copy(self.root.join(&matched.id), &path)
+                .map_err(|e| format!("Failed to copy {}: {e}", matched.id))
+        };
+        let bytes = match copied {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                let _ = std::fs::remove_file(&path);
+                return Err(e);
+            }
+        };
+        on_progress(&TrackProgress {
+            downloaded_bytes: bytes,
+            total_bytes: Some(bytes),
+            percent: Some(100.0),
+            speed: None,
+            eta_secs: Some(0),
+        });
+        Ok(path)
+    }
+}
+
+fn file_stem(entry: &str) -> &str {
+    let name = entry.rsplit(['/', '\\']).next().unwrap_or(entry);
+    name.rsplit_once('.').map_or(name, |(stem, _)| stem)
+}
+
+/// Normalized file name without a leading track number or `artist -` prefix.
+fn clean_name(stem: &str, artist: &str) -> String {
+    let name = normalize(stem);
+    let name = name.trim_start_matches(|c: char| c.is_ascii_digit() || c == ' ');
+    let name = name
+        .strip_prefix(artist)
+        .filter(|_| !artist.is_empty())
+        .map_or(name, str::trim_start);
+    name.to_string()
+}
+
+fn list_folder(root: &Path) -> Result<Vec<String>, String> {
+    if !root.is_dir() {
+        return Err(format!("{} is not a folder", root.display()));
+    }
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if audio_extension(&entry.file_name().to_string_lossy()).is_some() {
+                if let Ok(relative) = path.strip_prefix(root) {
+                    files.push(relative.to_string_lossy().to_string());
+                }
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn list_zip(path: &Path) -> Result<Vec<String>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    let archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Not a valid zip archive: {e}"))?;
+    let mut names: Vec<String> = archive
+        .file_names()
+        .filter(|name| !name.ends_with('/') && audio_extension(name).is_some())
+        .map(str::to_string)
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Extract one archive entry to `dest`, returning its size.
+fn extract(archive_path: &Path, entry: &str, dest: &Path) -> Result<u64, String> {
+    let file = File::open(archive_path)
+        .map_err(|e| format!("Failed to open {}: {e}", archive_path.display()))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Not a valid zip archive: {e}"))?;
+    let mut reader = archive
+        .by_name(entry)
+        .map_err(|e| format!("Failed to read {entry}: {e}"))?;
+    let mut out = File::create(dest).map_err(|e| format!("Failed to create file: {e}"))?;
+    std::io::copy(&mut reader, &mut out)
+        .map_err(|e| format!("Failed to extract {entry}: {e}"))
+}
@@ -0,0 +1,88 @@
+//! Backends that tracks are downloaded from.
+//!
+//! Every queue item records the [`Source`] it uses. The worker only talks to
+//! the [`DownloadSource`] trait: it asks the backend to `search` for each
+//! track of an album (or `resolve` a reference the user gave directly), then
+//! to `fetch` the match into the staging area. Sources that don't transcode
+//! keep the file's own format, so the caller uses whatever extension `fetch`
+//! returns.
+
+mod http;
+mod local;
+mod ytdlp;
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::matching::TrackMatch;
+use super::progress::TrackProgress;
+use super::DownloaderConfig;
+
+/// Which backend a queue item downloads from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Source {
+    /// YouTube search, downloaded and converted by yt-dlp
+    #[default]
+    YtDlp,
+    /// Direct links to audio files
+    Http,
+    /// Audio files in a local folder or `.zip` archive
+    Local { path: String },
+}
+
+impl Source {
+    pub fn backend(&self) -> Box<dyn DownloadSource> {
+        match self {
+            Source::YtDlp => Box::new(ytdlp::YtDlpSource),
+            Source::Http => Box::new(http::HttpSource),
+            Source::Local { path } => Box::new(local::LocalSource::new(Path::new(path))),
+        }
+    }
+}
+
+/// The track a source is asked to find.
+pub struct TrackQuery<'a> {
+    pub artist: &'a str,
+    pub title: &'a str,
+    /// Recording length, when MusicBrainz knows it
+    pub expected_secs: Option<f64>,
+}
+
+/// Settings and the stop check for one source call.
+pub struct FetchContext<'a> {
+    pub config: &'a DownloaderConfig,
+    pub should_stop: &'a dyn Fn() -> bool,
+}
+
+pub trait DownloadSource: Send + Sync {
+    /// Human-readable name for messages ("YouTube", ...).
+    fn name(&self) -> &'static str;
+
+    /// Find the best match for a track, or `None` if the source has nothing.
+    fn search(&self, query: &TrackQuery, ctx: &FetchContext) -> Result<Option<TrackMatch>, String>;
+
+    /// Turn a reference the user picked (video ID, URL, file name) into a match.
+    fn resolve(&self, reference: &str, ctx: &FetchContext) -> Result<TrackMatch, String>;
+
+    /// Save `matched` at `dest`, or next to it with the source's own
+    /// extension, and return the path written. Nothing is left behind on
+    /// failure.
+    fn fetch(
+        &self,
+        matched: &TrackMatch,
+        dest: &Path,
+        ctx: &FetchContext,
+        on_progress: &mut dyn FnMut(&TrackProgress),
+    ) -> Result<PathBuf, String>;
+}
+
+/// Extensions of files that sources copy as they are.
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "flac", "ogg", "opus", "m4a", "aac", "wav"];
+
+/// The lowercased extension of `name`, if it is an audio file.
+fn audio_extension(name: &str) -> Option<String> {
+    let ext = Path::new(name).extension()?.to_str()?.to_ascii_lowercase();
+    AUDIO_EXTENSIONS.contains(&ext.as_str()).then_some(ext)
+}
@@ -0,0 +1,180 @@
+//! YouTube through yt-dlp: scored search, then download and conversion to
+//! the configured audio format.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::super::matching::{self, Candidate, TrackMatch};
+use super::super::progress::{self, TrackProgress};
+use super::super::{ffmpeg_dir, process, yt_dlp_path, DownloaderConfig};
+use super::{DownloadSource, FetchContext, TrackQuery};
+
+pub struct YtDlpSource;
+
+impl DownloadSource for YtDlpSource {
+    fn name(&self) -> &'static str {
+        "YouTube"
+    }
+
+    fn search(&self, query: &TrackQuery, ctx: &FetchContext) -> Result<Option<TrackMatch>, String> {
+        search_youtube(query.artist, query.title, query.expected_secs, ctx)
+    }
+
+    fn resolve(&self, reference: &str, _ctx: &FetchContext) -> Result<TrackMatch, String> {
+        // Picked by the user from `downloader_search_songs`, so nothing to score
+        Ok(TrackMatch {
+            id: reference.to_string(),
+            title: reference.to_string(),
+            channel: String::new(),
+            duration: None,
+            score: 0.0,
+        })
+    }
+
+    fn fetch(
+        &self,
+        matched: &TrackMatch,
+        dest: &Path,
+        ctx: &FetchContext,
+        on_progress: &mut dyn FnMut(&TrackProgress),
+    ) -> Result<PathBuf, String> {
+        download_track(&matched.id, dest, ctx.config, ctx.should_stop, on_progress)?;
+        Ok(dest.to_path_buf())
+    }
+}
+
+/// Search YouTube and pick the best-scoring upload for the track.
+fn search_youtube(
+    artist: &str,
+    track: &str,
+    expected_secs: Option<f64>,
+    ctx: &FetchContext,
+) -> Result<Option<TrackMatch>, String> {
+    let query = format!("{artist} {track}");
+    let mut cmd = Command::new(yt_dlp_path());
+    cmd.args([
+        "--no-update",
+        "--flat-playlist",
+        "-j",
+        &format!("ytsearch{}:{query}", matching::CANDIDATES),
+    ]);
+
+    let mut lines = Vec::new();
+    let exit = process::run(cmd, ctx.config.stall_timeout(), ctx.should_stop, |line| {
+        lines.push(line.to_string())
+    });
+    match exit {
+        Ok(process::Exit::Finished(status)) if status.success() => {}
+        Ok(process::Exit::Stopped) => return Err("Cancelled".into()),
+        Err(e) => return Err(format!("Failed to run yt-dlp: {e}")),
+        _ => return Err("YouTube search failed".into()),
+    }
+
+    let candidates: Vec<Candidate> = lines
+        .iter()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter_map(|json| {
+            Some(Candidate {
+                id: json["id"].as_str().filter(|id| !id.is_empty())?.to_string(),
+                title: json["title"].as_str().unwrap_or("").to_string(),
+                channel: json["channel"]
+                    .as_str()
+                    .or_else(|| json["uploader"].as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                duration: json["duration"].as_f64(),
+            })
+        })
+        .collect();
+
+    let Some(matched) = matching::best(&candidates, artist, track, expected_secs) else {
+        return Ok(None);
+    };
+    log::info!(
+        "Matched \"{artist} - {track}\" to {} \"{}\" ({}), score {:.1}",
+        matched.id,
+        matched.title,
+        matched.channel,
+        matched.score
+    );
+    Ok(Some(matched))
+}
+
+/// Download and convert one video to `filepath`, reporting progress as
+/// yt-dlp prints it. On failure, whatever the run left behind is deleted.
+fn download_track(
+    vid_id: &str,
+    filepath: &Path,
+    config: &DownloaderConfig,
+    should_stop: &dyn Fn() -> bool,
+    on_progress: &mut dyn FnMut(&TrackProgress),
+) -> Result<(), String> {
+    let output_path = ytdlp_output_template(filepath);
+    let url = format!("https://www.youtube.com/watch?v={vid_id}");
+    let mut cmd = Command::new(yt_dlp_path());
+    cmd.args([
+        "--no-update",
+        "--extractor-args",
+        "youtube:player_client=android",
+    ]);
+    cmd.args(progress::ytdlp_args());
+    if let Some(kib) = config.rate_limit_per_download() {
+        cmd.args(["--limit-rate", &format!("{kib}K")]);
+    }
+    cmd.args(config.audio_format.ytdlp_args(&config.audio_quality));
+    cmd.args(["-o", &output_path, &url]);
+
+    if let Some(dir) = ffmpeg_dir() {
+        cmd.arg("--ffmpeg-location");
+        cmd.arg(&dir);
+    }
+
+    let exit = process::run(cmd, config.stall_timeout(), should_stop, |line| {
+        if let Some(p) = progress::parse_line(line) {
+            on_progress(&p);
+        }
+    });
+
+    let result = match exit {
+        Ok(process::Exit::Finished(status)) if status.success() => Ok(()),
+        Ok(process::Exit::Finished(_)) => Err("Download failed".to_string()),
+        Ok(process::Exit::Stopped) => Err("Cancelled".to_string()),
+        Ok(process::Exit::Stalled) => Err("Download stalled and was stopped".to_string()),
+        Err(e) => Err(format!("Failed to run yt-dlp: {e}")),
+    };
+    remove_partial_files(filepath, result.is_ok());
+    result
+}
+
+/// yt-dlp output template for `filepath`: same name, extension chosen by yt-dlp.
+fn ytdlp_output_template(filepath: &Path) -> String {
+    // '%' starts a yt-dlp template field, so escape any in titles
+    filepath
+        .with_extension("%(ext)s")
+        .to_string_lossy()
+        .replace('%', "%%")
+        .replace("%%(ext)s", "%(ext)s")
+}
+
+/// Delete yt-dlp's leftovers for `filepath`: `.part`, `.ytdl` and `.temp`
+/// files and unconverted source streams (`.webm`, `.f251.webm`, ...). They
+/// all share the target's stem. The converted file is kept if `keep_final`.
+fn remove_partial_files(filepath: &Path, keep_final: bool) {
+    let (Some(dir), Some(stem)) = (filepath.parent(), filepath.file_stem()) else {
+        return;
+    };
+    let prefix = format!("{}.", stem.to_string_lossy());
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_leftover = entry.file_name().to_string_lossy().starts_with(&prefix)
+            && (!keep_final || path != filepath);
+        if is_leftover && path.is_file() {
+            if let Err(e) = std::fs::remove_file(&path) {
+                log::warn!("Failed to remove {}: {e}", path.display());
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Staging area for files that are still being downloaded and tagged.
+//!
+//! Tracks are written under a hidden directory in the music directory, at the
+//! same relative path they will have in the library, and are only renamed into
+//! place once tagged. Keeping staging on the same filesystem makes that rename
+//! atomic, so a library scanner never sees a half-written or untagged file.
+//! Nothing is running at startup, so whatever is left in staging then is an
+//! orphan from a crash and is deleted.
+
+use std::path::{Path, PathBuf};
+
+const STAGING_DIR: &str = ".lumina-staging";
+
+fn root(music_dir: &Path) -> PathBuf {
+    music_dir.join(STAGING_DIR)
+}
+
+/// Where archives being imported are unpacked. Cleared once the import is
+/// done, or by the next `sweep`.
+pub fn import_dir(music_dir: &Path) -> PathBuf {
+    root(music_dir).join(".import")
+}
+
+/// Delete everything unpacked for imports.
+pub fn clear_imports(music_dir: &Path) {
+    let dir = import_dir(music_dir);
+    if dir.exists() {
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            log::warn!("Failed to clean up {}: {e}", dir.display());
+        }
+    }
+}
+
+/// Where to write `final_path` (a file under `music_dir`) until it is finished.
+pub fn staged_path(music_dir: &Path, final_path: &Path) -> PathBuf {
+    let relative = final_path.strip_prefix(music_dir).unwrap_or(final_path);
+    let relative = relative
+        .components()
+        .filter(|c| matches!(c, std::path::Component::Normal(_)))
+        .collect::<PathBuf>();
+    root(music_dir).join(relative)
+}
+
+/// Create the staging directory for `staged`.
+pub fn prepare(music_dir: &Path, staged: &Path) -> Result<(), String> {
+    let root = root(music_dir);
+    std::fs::create_dir_all(&root).map_err(|e| format!("Failed to create staging directory: {e}"))?;
+    // Scanners skip hidden directories; Navidrome also skips any with an `.ndignore`
+    let _ = std::fs::write(root.join(".ndignore"), "");
+    if let Some(dir) = staged.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create staging directory: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Move a finished file into the library.
+pub fn commit(music_dir: &Path, staged: &Path, final_path: &Path) -> Result<(), String> {
+    if let Some(dir) = final_path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory: {e}"))?;
+    }
+    if std::fs::rename(staged, final_path).is_err() {
+        // Another filesystem mounted inside the music directory: copy next to
+        // the target under a hidden name, then rename that into place
+        let name = final_path.file_name().unwrap_or_default().to_string_lossy();
+        let tmp = final_path.with_file_name(format!(".{name}.tmp"));
+        std::fs::copy(staged, &tmp)
+            .and_then(|_| std::fs::rename(&tmp, final_path))
+            .map_err(|e| {
+                let _ = std::fs::remove_file(&tmp);
+                format!("Failed to move file into the library: {e}")
+            })?;
+        let _ = std::fs::remove_file(staged);
+    }
+    prune(music_dir, staged);
+    Ok(())
+}
+
+/// Delete a staged file that won't be finished.
+pub fn discard(music_dir: &Path, staged: &Path) {
+    let _ = std::fs::remove_file(staged);
+    prune(music_dir, staged);
+}
+
+/// Remove the now-empty directories above `staged`, up to the staging root.
+fn prune(music_dir: &Path, staged: &Path) {
+    let root = root(music_dir);
+    let mut dir = staged.parent();
+    while let Some(d) = dir.filter(|d| d.starts_with(&root) && *d != root) {
+        // Fails on the first directory that still has files in it
+        if std::fs::remove_dir(d).is_err() {
+            break;
+        }
+        dir = d.parent();
+    }
+}
+
+/// Delete everything left in staging by a previous run.
+pub fn sweep(music_dir: &Path) {
+    let root = root(music_dir);
+    if !root.exists() {
+        return;
+    }
+    match std::fs::remove_dir_all(&root) {
+        Ok(()) => log::info!("Removed leftover staging data in {}", root.display()),
+        Err(e) => log::warn!("Failed to clean up {}: {e}", root.display()),
+    }
+}
@@ -0,0 +1,244 @@
+//! Format-aware tag writing for downloaded tracks.
+//!
+//! MP3 files get ID3v2.4 through the `id3` crate. FLAC, Ogg Vorbis and Opus
+//! get Vorbis comments with the cover as a METADATA_BLOCK_PICTURE, and M4A
+//! gets MP4 `ilst` atoms; those go through `lofty`. Every format carries the
+//! same fields, and existing tags are replaced rather than merged.
+
+use std::path::Path;
+
+use id3::TagLike;
+use lofty::config::WriteOptions;
+use lofty::file::TaggedFileExt;
+use lofty::picture::{MimeType, Picture, PictureType};
+use lofty::tag::{Accessor, ItemKey, Tag, TagExt};
+
+/// Metadata written to a downloaded track.
+#[derive(Debug, Clone, Copy)]
+pub struct TrackTags<'a> {
+    pub title: &'a str,
+    pub artist: &'a str,
+    pub album: &'a str,
+    pub album_artist: &'a str,
+    pub year: &'a str,
+    /// Track number and total within the disc
+    pub track: usize,
+    pub total_tracks: usize,
+    pub disc: usize,
+    pub total_discs: usize,
+    pub genre: &'a str,
+    /// MusicBrainz release and release group, when known
+    pub release_id: Option<&'a str>,
+    pub release_group_id: Option<&'a str>,
+    /// JPEG or PNG front cover
+    pub cover: Option<&'a [u8]>,
+}
+
+/// Write `tags` to the file at `path`, choosing the tag format from its extension.
+pub fn write_tags(path: &Path, tags: &TrackTags) -> Result<(), String> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("mp3") => write_id3(path, tags),
+        Some("flac" | "ogg" | "opus" | "m4a") => write_native(path, tags),
+        _ => Err(format!("Don't know how to tag {}", path.display())),
+    }
+}
+
+pub fn is_png(data: &[u8]) -> bool {
+    data.len() > 3 && data[0..3] == [0x89, 0x50, 0x4E]
+}
+
+fn write_id3(path: &Path, tags: &TrackTags) -> Result<(), String> {
+    let mut tag = id3::Tag::new();
+
+    tag.set_title(tags.title);
+    tag.set_artist(tags.artist);
+    tag.set_album(tags.album);
+    tag.set_album_artist(tags.album_artist);
+
+    if !tags.year.is_empty() {
+        tag.set_year(tags.year.parse::<i32>().unwrap_or(0));
+    }
+
+    tag.set_track(tags.track as u32);
+    tag.set_total_tracks(tags.total_tracks as u32);
+    // TPOS
+    tag.set_disc(tags.disc as u32);
+    tag.set_total_discs(tags.total_discs as u32);
+    tag.set_genre(tags.genre);
+
+    // TXXX frames, named the way MusicBrainz Picard names them
+    if let Some(id) = tags.release_id {
+        tag.add_frame(id3::frame::ExtendedText {
+            description: "MusicBrainz Album Id".to_string(),
+            value: id.to_string(),
+        });
+    }
+    if let Some(id) = tags.release_group_id {
+        tag.add_frame(id3::frame::ExtendedText {
+            description: "MusicBrainz Release Group Id".to_string(),
+            value: id.to_string(),
+        });
+    }
+
+    if let Some(data) = tags.cover {
+        let mime = if is_png(data) { "image/png" } else { "image/jpeg" };
+        tag.add_frame(id3::frame::Picture {
+            mime_type: mime.to_string(),
+            picture_type: id3::frame::PictureType::CoverFront,
+            description: "Cover".to_string(),
+            data: data.to_vec(),
+        });
+    }
+
+    tag.write_to_path(path, id3::Version::Id3v24)
+        .map_err(|e| format!("Failed to write ID3 tag: {e}"))
+}
+
+/// Vorbis comments (FLAC, Ogg, Opus) or MP4 atoms (M4A), whichever the file uses.
+fn write_native(path: &Path, tags: &TrackTags) -> Result<(), String> {
+    let file = lofty::read_from_path(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let mut tag = Tag::new(file.primary_tag_type());
+
+    tag.set_title(tags.title.to_string());
+    tag.set_artist(tags.artist.to_string());
+    tag.set_album(tags.album.to_string());
+    tag.insert_text(ItemKey::AlbumArtist, tags.album_artist.to_string());
+
+    if !tags.year.is_empty() {
+        // DATE in Vorbis comments, ©day in MP4
+        tag.insert_text(ItemKey::RecordingDate, tags.year.to_string());
+    }
+
+    tag.set_track(tags.track as u32);
+    tag.set_track_total(tags.total_tracks as u32);
+    tag.set_disk(tags.disc as u32);
+    tag.set_disk_total(tags.total_discs as u32);
+    tag.set_genre(tags.genre.to_string());
+
+    if let Some(id) = tags.release_id {
+        tag.insert_text(ItemKey::MusicBrainzReleaseId, id.to_string());
+    }
+    if let Some(id) = tags.release_group_id {
+        tag.insert_text(ItemKey::MusicBrainzReleaseGroupId, id.to_string());
+    }
+
+    if let Some(data) = tags.cover {
+        let mime = if is_png(data) { MimeType::Png } else { MimeType::Jpeg };
+        tag.push_picture(Picture::new_unchecked(
+            PictureType::CoverFront,
+            Some(mime),
+            Some("Cover".to_string()),
+            data.to_vec(),
+        ));
+    }
+
+    tag.save_to_path(path, WriteOptions::default())
+        .map_err(|e| format!("Failed to write tags: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::PathBuf;
+
+    const RELEASE_ID: &str = "b84ee12a-09ef-421b-82de-0441a926375b";
+    const RELEASE_GROUP_ID: &str = "f5093c06-23e3-404f-aeaa-40f72885ee3a";
+    const PNG: &[u8] = &[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 0];
+
+    fn sample_tags() -> TrackTags<'static> {
+        TrackTags {
+            title: "Título",
+            artist: "Artist",
+            album: "Album",
+            album_artist: "Album Artist",
+            year: "1999",
+            track: 3,
+            total_tracks: 12,
+            disc: 2,
+            total_discs: 2,
+            genre: "Rock",
+            release_id: Some(RELEASE_ID),
+            release_group_id: Some(RELEASE_GROUP_ID),
+            cover: Some(PNG),
+        }
+    }
+
+    /// Copy the untagged fixture `name` somewhere it can be written to.
+    fn fixture(name: &str) -> PathBuf {
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name);
+        let dir = std::env::temp_dir().join(format!("tags-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::copy(src, &path).unwrap();
+        path
+    }
+
+    fn assert_round_trip(name: &str) {
+        let path = fixture(name);
+        write_tags(&path, &sample_tags()).unwrap();
+        // Writing again replaces the tags instead of adding to them
+        write_tags(&path, &sample_tags()).unwrap();
+
+        let file = lofty::read_from_path(&path).unwrap();
+        let tag = file.primary_tag().expect("no tag written");
+        assert_eq!(tag.title().as_deref(), Some("Título"));
+        assert_eq!(tag.artist().as_deref(), Some("Artist"));
+        assert_eq!(tag.album().as_deref(), Some("Album"));
+        assert_eq!(tag.get_string(&ItemKey::AlbumArtist), Some("Album Artist"));
+        assert_eq!(tag.genre().as_deref(), Some("Rock"));
+        assert_eq!(tag.track(), Some(3));
+        assert_eq!(tag.track_total(), Some(12));
+        assert_eq!(tag.disk(), Some(2));
+        assert_eq!(tag.disk_total(), Some(2));
+        assert_eq!(tag.get_string(&ItemKey::MusicBrainzReleaseId), Some(RELEASE_ID));
+        assert_eq!(
+            tag.get_string(&ItemKey::MusicBrainzReleaseGroupId),
+            Some(RELEASE_GROUP_ID)
+        );
+        assert_eq!(tag.pictures().len(), 1);
+        // MP4 `covr` atoms have no picture type to read back
+        if file.file_type() != lofty::file::FileType::Mp4 {
+            assert_eq!(tag.pictures()[0].pic_type(), PictureType::CoverFront);
+        }
+        assert_eq!(tag.pictures()[0].mime_type(), Some(&MimeType::Png));
+        assert_eq!(tag.pictures()[0].data(), PNG);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn mp3_round_trip() {
+        assert_round_trip("silence.mp3");
+    }
+
+    #[test]
+    fn flac_round_trip() {
+        assert_round_trip("silence.flac");
+    }
+
+    #[test]
+    fn m4a_round_trip() {
+        assert_round_trip("silence.m4a");
+    }
+
+    #[test]
+    fn ogg_round_trip() {
+        assert_round_trip("silence.ogg");
+    }
+
+    #[test]
+    fn opus_round_trip() {
+        assert_round_trip("silence.opus");
+    }
+
+    #[test]
+    fn unknown_format_is_rejected() {
+        assert!(write_tags(Path::new("track.wav"), &sample_tags()).is_err());
+    }
+}
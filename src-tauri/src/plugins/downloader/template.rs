@@ -0,0 +1,196 @@
+//! File naming templates for downloaded tracks.
+//!
+//! A template is a relative path with `{field}` placeholders, e.g.
+//! `{albumartist}/{year} - {album}/{disc}-{track:02} {title}.{ext}`.
+//! Numeric fields accept a zero-padding width (`{track:02}`). Field values are
+//! sanitized, so `/` in a template is the only way to create directories.
+//!
+//! `{track}` and `{totaltracks}` count within a disc. For multi-disc albums a
+//! template without `{disc}` gets a `{disc}-` file name prefix, so discs
+//! never overwrite each other's tracks.
+
+use std::path::PathBuf;
+
+use super::sanitize_filename;
+
+/// Values available to a template for one track.
+#[derive(Debug, Clone, Default)]
+pub struct TrackFields<'a> {
+    pub artist: &'a str,
+    pub album_artist: &'a str,
+    pub album: &'a str,
+    pub year: &'a str,
+    pub genre: &'a str,
+    pub title: &'a str,
+    pub track: usize,
+    pub total_tracks: usize,
+    pub disc: usize,
+    pub total_discs: usize,
+    pub ext: &'a str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Artist,
+    AlbumArtist,
+    Album,
+    Year,
+    Genre,
+    Title,
+    Track,
+    TotalTracks,
+    Disc,
+    TotalDiscs,
+    Ext,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "artist" => Field::Artist,
+            "albumartist" => Field::AlbumArtist,
+            "album" => Field::Album,
+            "year" => Field::Year,
+            "genre" => Field::Genre,
+            "title" => Field::Title,
+            "track" => Field::Track,
+            "totaltracks" => Field::TotalTracks,
+            "disc" => Field::Disc,
+            "totaldiscs" => Field::TotalDiscs,
+            "ext" => Field::Ext,
+            _ => return None,
+        })
+    }
+
+    fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            Field::Track | Field::TotalTracks | Field::Disc | Field::TotalDiscs
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Literal(String),
+    Field { field: Field, width: usize },
+}
+
+/// A parsed naming template.
+#[derive(Debug, Clone)]
+pub struct PathTemplate {
+    segments: Vec<Segment>,
+}
+
+impl PathTemplate {
+    /// Parse and validate a template.
+    ///
+    /// The template must be relative, must not contain `..` components and
+    /// must end in `.{ext}` so the file extension follows the audio format.
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' => {
+                    let mut spec = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => spec.push(c),
+                            None => return Err(format!("Unclosed '{{' in template: {template}")),
+                        }
+                    }
+                    let (name, width) = match spec.split_once(':') {
+                        Some((name, width)) => {
+                            let width = width
+                                .parse::<usize>()
+                                .map_err(|_| format!("Invalid width in {{{spec}}}"))?;
+                            (name, width)
+                        }
+                        None => (spec.as_str(), 0),
+                    };
+                    let field = Field::parse(name)
+                        .ok_or_else(|| format!("Unknown template field: {{{name}}}"))?;
+                    if width > 0 && !field.is_numeric() {
+                        return Err(format!("Only numeric fields take a width: {{{spec}}}"));
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field { field, width });
+                }
+                '}' => return Err(format!("Unmatched '}}' in template: {template}")),
+                _ => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        if template.starts_with('/') || template.starts_with('\\') {
+            return Err("Template must be a relative path".into());
+        }
+        if template.split(['/', '\\']).any(|part| part.trim() == "..") {
+            return Err("Template must not contain '..'".into());
+        }
+        let ends_with_ext = matches!(
+            segments.as_slice(),
+            [.., Segment::Literal(dot), Segment::Field { field: Field::Ext, .. }] if dot.ends_with('.')
+        );
+        if !ends_with_ext {
+            return Err("Template must end with .{ext}".into());
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Render the template to a path relative to the library root.
+    pub fn render(&self, fields: &TrackFields) -> PathBuf {
+        let mut rendered = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => rendered.push_str(text),
+                Segment::Field { field, width } => {
+                    let value = match field {
+                        Field::Artist => sanitize_filename(fields.artist),
+                        Field::AlbumArtist => sanitize_filename(fields.album_artist),
+                        Field::Album => sanitize_filename(fields.album),
+                        Field::Year => sanitize_filename(fields.year),
+                        Field::Genre => sanitize_filename(fields.genre),
+                        Field::Title => sanitize_filename(fields.title),
+                        Field::Ext => sanitize_filename(fields.ext),
+                        Field::Track => format!("{:0width$}", fields.track),
+                        Field::TotalTracks => format!("{:0width$}", fields.total_tracks),
+                        Field::Disc => format!("{:0width$}", fields.disc),
+                        Field::TotalDiscs => format!("{:0width$}", fields.total_discs),
+                    };
+                    rendered.push_str(&value);
+                }
+            }
+        }
+
+        // Empty fields can leave blank or dangling components ("/ - Album/");
+        // drop them rather than create oddly named directories
+        let mut path: PathBuf = rendered
+            .split(['/', '\\'])
+            .map(|part| part.trim())
+            .filter(|part| !part.is_empty() && *part != "." && *part != "..")
+            .collect();
+
+        if fields.total_discs > 1 && !self.uses(Field::Disc) {
+            if let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) {
+                path.set_file_name(format!("{}-{name}", fields.disc));
+            }
+        }
+        path
+    }
+
+    fn uses(&self, wanted: Field) -> bool {
+        self.segments
+            .iter()
+            .any(|s| matches!(s, Segment::Field { field, .. } if *field == wanted))
+    }
+}
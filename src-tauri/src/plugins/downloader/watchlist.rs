@@ -0,0 +1,177 @@
+//! Followed artists, checked on a schedule for new releases.
+//!
+//! Following an artist records every release group MusicBrainz lists for them
+//! at that moment, so only releases that appear later count as new. Each check
+//! re-reads the discography and reports the release groups not seen before;
+//! ones dated in the future stay unseen until their release date. The list is
+//! stored next to the download journal and rewritten atomically on change.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use super::MbAlbum;
+
+const WATCHLIST_FILE: &str = "watchlist.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchedArtist {
+    /// MusicBrainz artist ID
+    pub id: String,
+    pub name: String,
+    /// Genre tagged on albums queued automatically
+    #[serde(default)]
+    pub genre: String,
+    /// Release group IDs already reported, owned or there when followed
+    #[serde(default)]
+    pub seen: Vec<String>,
+    /// Unix time of the last successful check
+    #[serde(default)]
+    pub last_checked: Option<i64>,
+}
+
+/// Location of the watchlist file.
+pub fn watchlist_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(WATCHLIST_FILE))
+}
+
+pub struct Watchlist {
+    /// `None` when there is no app data directory; the list then lives in memory only
+    path: Option<PathBuf>,
+    artists: Mutex<Vec<WatchedArtist>>,
+    /// Held while a check runs, so scheduled and manual checks don't overlap
+    pub checking: Mutex<()>,
+}
+
+impl Watchlist {
+    pub fn open(path: Option<PathBuf>) -> Self {
+        let artists = path
+            .as_deref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|json| match serde_json::from_str(&json) {
+                Ok(artists) => Some(artists),
+                Err(e) => {
+                    log::warn!("Ignoring unreadable watchlist: {e}");
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            path,
+            artists: Mutex::new(artists),
+            checking: Mutex::new(()),
+        }
+    }
+
+    pub fn artists(&self) -> Vec<WatchedArtist> {
+        self.artists.lock().unwrap().clone()
+    }
+
+    /// Start following an artist whose current discography is `discography`.
+    /// Following again keeps what was seen and updates the name and genre.
+    pub fn follow(
+        &self,
+        id: &str,
+        name: &str,
+        genre: &str,
+        discography: &[MbAlbum],
+    ) -> WatchedArtist {
+        self.update(|artists| {
+            let index = match artists.iter().position(|a| a.id == id) {
+                Some(i) => i,
+                None => {
+                    artists.push(WatchedArtist {
+                        id: id.to_string(),
+                        name: String::new(),
+                        genre: String::new(),
+                        seen: Vec::new(),
+                        last_checked: None,
+                    });
+                    artists.len() - 1
+                }
+            };
+            let artist = &mut artists[index];
+            artist.name = name.to_string();
+            artist.genre = genre.to_string();
+            let today = today();
+            for album in discography.iter().filter(|a| is_released(a, &today)) {
+                if !artist.seen.contains(&album.id) {
+                    artist.seen.push(album.id.clone());
+                }
+            }
+            artist.last_checked = Some(Local::now().timestamp());
+            artist.clone()
+        })
+    }
+
+    pub fn unfollow(&self, id: &str) {
+        self.update(|artists| artists.retain(|a| a.id != id));
+    }
+
+    /// Artists not checked within the last `interval_hours`.
+    pub fn due(&self, interval_hours: u64) -> Vec<WatchedArtist> {
+        let cutoff = Local::now().timestamp() - (interval_hours * 3600) as i64;
+        self.artists()
+            .into_iter()
+            .filter(|a| a.last_checked.map_or(true, |t| t <= cutoff))
+            .collect()
+    }
+
+    /// Release groups in `discography` not seen before, which are then marked
+    /// seen along with the time of the check.
+    pub fn new_releases(&self, id: &str, discography: &[MbAlbum]) -> Vec<MbAlbum> {
+        let today = today();
+        self.update(|artists| {
+            let mut found = Vec::new();
+            let Some(artist) = artists.iter_mut().find(|a| a.id == id) else {
+                // Unfollowed while the check ran
+                return found;
+            };
+            for album in discography.iter().filter(|a| is_released(a, &today)) {
+                if !artist.seen.contains(&album.id) {
+                    artist.seen.push(album.id.clone());
+                    found.push(album.clone());
+                }
+            }
+            artist.last_checked = Some(Local::now().timestamp());
+            found
+        })
+    }
+
+    fn update<T>(&self, f: impl FnOnce(&mut Vec<WatchedArtist>) -> T) -> T {
+        let mut artists = self.artists.lock().unwrap();
+        let result = f(&mut artists);
+        if let Some(path) = &self.path {
+            if let Err(e) = write_atomic(path, &artists) {
+                log::warn!("Failed to write watchlist: {e}");
+            }
+        }
+        result
+    }
+}
+
+fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+/// Whether `album` is out by `today` (`YYYY-MM-DD`). Partial dates such as
+/// `2025` or `2025-06` compare as their first day; undated ones count as out.
+fn is_released(album: &MbAlbum, today: &str) -> bool {
+    album.release_date.is_empty() || album.release_date.as_str() <= today
+}
+
+fn write_atomic(path: &Path, artists: &[WatchedArtist]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string(artists).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
@@ -0,0 +1,18 @@
+//! Randomness from the operating system's generator.
+
+/// `N` random bytes.
+pub fn bytes<const N: usize>() -> [u8; N] {
+    let mut buf = [0u8; N];
+    getrandom::getrandom(&mut buf).expect("OS random number generator is unavailable");
+    buf
+}
+
+pub fn next_u64() -> u64 {
+    u64::from_le_bytes(bytes())
+}
+
+/// `N` random bytes as lowercase hex.
+#[cfg(feature = "remote-api")]
+pub fn hex<const N: usize>() -> String {
+    bytes::<N>().iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,70 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::remote::server::{generate_token, RemoteServer};
+
+/// Default listen address; use `0.0.0.0:7878` to accept phones on the LAN.
+const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1:7878";
+
+/// Tauri-managed slot for the (optional) running remote-control server.
+#[derive(Default)]
+pub struct RemoteApiState {
+    server: Mutex<Option<RemoteServer>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteApiStatus {
+    pub running: bool,
+    pub address: Option<String>,
+    /// Access token clients must present; only reported while running.
+    pub token: Option<String>,
+}
+
+impl RemoteApiState {
+    fn status(&self) -> RemoteApiStatus {
+        let server = self.server.lock();
+        RemoteApiStatus {
+            running: server.is_some(),
+            address: server.as_ref().map(|s| s.address().to_string()),
+            token: server.as_ref().map(|s| s.token().to_string()),
+        }
+    }
+}
+
+/// Start the remote-control API, restarting it if it is already running.
+///
+/// A random token is generated when none is given.
+#[tauri::command]
+pub fn remote_api_start(
+    app: AppHandle,
+    bind_address: Option<String>,
+    token: Option<String>,
+    state: State<'_, RemoteApiState>,
+) -> Result<RemoteApiStatus, String> {
+    let bind_address = bind_address.unwrap_or_else(|| DEFAULT_BIND_ADDRESS.to_string());
+    let token = token
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(generate_token);
+    {
+        let mut server = state.server.lock();
+        if let Some(running) = server.take() {
+            running.stop();
+        }
+        *server = Some(RemoteServer::start(app, &bind_address, token)?);
+    }
+    Ok(state.status())
+}
+
+#[tauri::command]
+pub fn remote_api_stop(state: State<'_, RemoteApiState>) -> RemoteApiStatus {
+    if let Some(running) = state.server.lock().take() {
+        running.stop();
+    }
+    state.status()
+}
+
+#[tauri::command]
+pub fn remote_api_status(state: State<'_, RemoteApiState>) -> RemoteApiStatus {
+    state.status()
+}
@@ -0,0 +1,241 @@
+//! Just enough HTTP/1.1 for the remote API.
+//!
+//! Each connection carries one request, parsed with `httparse`, and the
+//! response closes it. Owning the socket (rather than handing it to an HTTP
+//! server crate) is what lets WebSocket connections set read timeouts.
+
+use std::io::{self, Read, Write};
+
+/// Largest request head accepted
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+const MAX_HEADERS: usize = 64;
+
+/// A request line, its headers and body.
+#[derive(Debug)]
+pub struct Request {
+    pub method: String,
+    /// Path without the query string
+    pub path: String,
+    /// Query string without the leading `?`
+    pub query: String,
+    headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Value of the header `name`, matched case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Read one request from `stream`, refusing bodies over `max_body` bytes.
+pub fn read_request<S: Read>(stream: &mut S, max_body: usize) -> Result<Request, String> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    let head_len = loop {
+        let n = stream
+            .read(&mut chunk)
+            .map_err(|e| format!("Failed to read request: {e}"))?;
+        if n == 0 {
+            return Err("Connection closed mid-request".into());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+
+        let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+        match httparse::Request::new(&mut headers)
+            .parse(&buf)
+            .map_err(|e| format!("Malformed request: {e}"))?
+        {
+            httparse::Status::Complete(len) => break len,
+            httparse::Status::Partial if buf.len() > MAX_HEAD_BYTES => {
+                return Err("Request head too large".into());
+            }
+            httparse::Status::Partial => {}
+        }
+    };
+
+    let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+    let mut parsed = httparse::Request::new(&mut headers);
+    parsed
+        .parse(&buf)
+        .map_err(|e| format!("Malformed request: {e}"))?;
+    let target = parsed.path.unwrap_or("/");
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = Request {
+        method: parsed.method.unwrap_or_default().to_string(),
+        path: path.to_string(),
+        query: query.to_string(),
+        headers: parsed
+            .headers
+            .iter()
+            .map(|h| {
+                let value = String::from_utf8_lossy(h.value).into_owned();
+                (h.name.to_string(), value)
+            })
+            .collect(),
+        body: Vec::new(),
+    };
+
+    let length = match request.header("Content-Length") {
+        Some(value) => value
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid Content-Length: {value}"))?,
+        None => 0,
+    };
+    if length > max_body {
+        return Err("Request body too large".into());
+    }
+    let mut body = buf.split_off(head_len);
+    body.truncate(length);
+    if body.len() < length {
+        let start = body.len();
+        body.resize(length, 0);
+        stream
+            .read_exact(&mut body[start..])
+            .map_err(|e| format!("Failed to read request body: {e}"))?;
+    }
+    request.body = body;
+    Ok(request)
+}
+
+/// A status, headers and body, written with `Connection: close`.
+#[derive(Debug)]
+pub struct Response {
+    pub status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    pub fn new(status: u16, body: Vec<u8>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    pub fn write_to<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, reason(self.status));
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        // Informational and empty responses must not carry a length
+        if self.status != 101 && self.status != 204 {
+            head.push_str(&format!("Content-Length: {}\r\n", self.body.len()));
+        }
+        if self.status != 101 {
+            head.push_str("Connection: close\r\n");
+        }
+        head.push_str("\r\n");
+        out.write_all(head.as_bytes())?;
+        out.write_all(&self.body)?;
+        out.flush()
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        101 => "Switching Protocols",
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        409 => "Conflict",
+        422 => "Unprocessable Entity",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hands out its data a few bytes per read, like a slow client.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.0.len()).min(7);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn parses_request_line_headers_and_body() {
+        let raw = b"POST /api/audio/seek?token=abc HTTP/1.1\r\n\
+            Host: localhost\r\n\
+            content-length: 15\r\n\
+            \r\n\
+            {\"timeSecs\":42}";
+        let request = read_request(&mut Trickle(raw), 1024).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/api/audio/seek");
+        assert_eq!(request.query, "token=abc");
+        assert_eq!(request.header("Content-Length"), Some("15"));
+        assert_eq!(request.header("HOST"), Some("localhost"));
+        assert_eq!(request.body, b"{\"timeSecs\":42}");
+    }
+
+    #[test]
+    fn request_without_body_or_query() {
+        let raw = b"GET /api/state HTTP/1.1\r\nAuthorization: Bearer t\r\n\r\n";
+        let request = read_request(&mut &raw[..], 1024).unwrap();
+        assert_eq!(request.path, "/api/state");
+        assert_eq!(request.query, "");
+        assert_eq!(request.header("authorization"), Some("Bearer t"));
+        assert!(request.body.is_empty());
+    }
+
+    #[test]
+    fn refuses_oversized_and_truncated_requests() {
+        let raw = b"POST / HTTP/1.1\r\nContent-Length: 2048\r\n\r\n";
+        assert!(read_request(&mut &raw[..], 1024).is_err());
+
+        let raw = b"POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nshort";
+        assert!(read_request(&mut &raw[..], 1024).is_err());
+
+        let raw = b"GET /api/state HTTP/1.1\r\nHost: local";
+        assert!(read_request(&mut &raw[..], 1024).is_err());
+
+        let raw = b"POST / HTTP/1.1\r\nContent-Length: ten\r\n\r\n";
+        assert!(read_request(&mut &raw[..], 1024).is_err());
+    }
+
+    #[test]
+    fn writes_length_and_close() {
+        let mut out = Vec::new();
+        Response::new(200, b"{}".to_vec())
+            .with_header("Content-Type", "application/json")
+            .write_to(&mut out)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+             Content-Length: 2\r\nConnection: close\r\n\r\n{}"
+        );
+
+        let mut out = Vec::new();
+        Response::new(204, Vec::new()).write_to(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n"
+        );
+    }
+}
@@ -0,0 +1,7 @@
+//! Optional HTTP/WebSocket API for controlling playback from other devices.
+
+pub mod commands;
+mod http;
+pub mod server;
+
+pub use commands::*;
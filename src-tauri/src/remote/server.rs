@@ -0,0 +1,573 @@
+//! Embedded HTTP server for the remote-control API.
+//!
+//! Routes (all require the access token):
+//! - `GET  /api/state`          current `AudioState` (mirrors `audio_get_state`)
+//! - `POST /api/audio/<name>`   mirrors the `audio_<name>` Tauri command, same JSON arguments
+//! - `GET  /api/cover`          cover art of the current track
+//! - `GET  /api/events`         WebSocket pushing `audio:*` events as `{ event, payload }`
+//!
+//! The token is accepted as `Authorization: Bearer <token>` or as a `?token=`
+//! query parameter, since browsers cannot set headers on WebSocket requests.
+//! No CORS headers are sent, so web pages on other origins can't use the API.
+
+use std::fs::File;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crossbeam_channel::TryRecvError;
+use serde::{Deserialize, Serialize};
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, StandardVisualKey, Visual};
+use symphonia::core::probe::Hint;
+use tauri::{AppHandle, Manager, State};
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+use super::http::{self, Request, Response};
+
+use crate::audio::engine::{AudioCommand, AudioEngineHandle};
+use crate::audio::error::AudioError;
+use crate::audio::events::{AudioStateEvent, EngineEvent};
+use crate::audio::source::TrackSource;
+use crate::audio::state::TrackInfo;
+use crate::random;
+
+/// How often blocking loops check for shutdown
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long a WebSocket read waits before events are forwarded again; also
+/// the most an event waits to be sent
+const SOCKET_READ_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Time a client gets to send its whole request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connections served at once, WebSockets included; more are turned away
+const MAX_CONNECTIONS: usize = 32;
+
+/// Largest request body accepted (a `play_track` payload is well under this)
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Cover files looked for next to a local track, by name without extension
+const COVER_FILE_NAMES: &[&str] = &["cover", "folder", "front", "album"];
+
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp"];
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlayTrackArgs {
+    track: TrackInfo,
+    source_url: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SeekArgs {
+    time_secs: f64,
+}
+
+#[derive(Deserialize)]
+struct SeekPercentArgs {
+    percent: f64,
+}
+
+#[derive(Deserialize)]
+struct VolumeArgs {
+    volume: f32,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// State shared by the accept thread and connection threads.
+struct Shared {
+    app: AppHandle,
+    token: String,
+    shutdown: AtomicBool,
+    /// Connections being served
+    connections: AtomicUsize,
+}
+
+impl Shared {
+    fn engine(&self) -> State<'_, AudioEngineHandle> {
+        self.app.state::<AudioEngineHandle>()
+    }
+}
+
+/// A running remote-control server.
+pub struct RemoteServer {
+    shared: Arc<Shared>,
+    address: SocketAddr,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RemoteServer {
+    /// Bind `bind_address` and start serving the API, protected by `token`.
+    pub fn start(app: AppHandle, bind_address: &str, token: String) -> Result<Self, String> {
+        let listener = TcpListener::bind(bind_address)
+            .map_err(|e| format!("Failed to bind {bind_address}: {e}"))?;
+        let address = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to read listener address: {e}"))?;
+        // Non-blocking accept so the thread can notice shutdown
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("Failed to configure listener: {e}"))?;
+
+        let shared = Arc::new(Shared {
+            app,
+            token,
+            shutdown: AtomicBool::new(false),
+            connections: AtomicUsize::new(0),
+        });
+
+        let accept_shared = shared.clone();
+        let thread = thread::Builder::new()
+            .name("lumina-remote".into())
+            .spawn(move || run_acceptor(accept_shared, listener))
+            .map_err(|e| format!("Failed to spawn remote API thread: {e}"))?;
+
+        log::info!("Remote API listening on http://{}", address);
+        Ok(Self {
+            shared,
+            address,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    pub fn token(&self) -> &str {
+        &self.shared.token
+    }
+
+    /// Stop serving; open WebSocket connections close within one poll interval.
+    pub fn stop(mut self) {
+        self.shared.shutdown.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        log::info!("Remote API on {} stopped", self.address);
+    }
+}
+
+/// Generate a random 128-bit access token as hex.
+pub fn generate_token() -> String {
+    random::hex::<16>()
+}
+
+fn run_acceptor(shared: Arc<Shared>, listener: TcpListener) {
+    while !shared.shutdown.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((mut stream, _)) => {
+                let Some(slot) = ConnectionSlot::claim(&shared) else {
+                    log::warn!("Remote API is at {MAX_CONNECTIONS} connections, refusing another");
+                    let busy = error_response(503, "Too many connections");
+                    let _ = busy.write_to(&mut stream);
+                    continue;
+                };
+                let spawned = thread::Builder::new()
+                    .name("lumina-remote-request".into())
+                    .spawn(move || handle_connection(&slot, stream));
+                if let Err(e) = spawned {
+                    log::warn!("Failed to spawn remote API request thread: {}", e);
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                thread::sleep(POLL_INTERVAL);
+            }
+            Err(e) => {
+                log::warn!("Remote API accept failed: {}", e);
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+/// One of the `MAX_CONNECTIONS` connection slots, given back on drop.
+struct ConnectionSlot(Arc<Shared>);
+
+impl ConnectionSlot {
+    fn claim(shared: &Arc<Shared>) -> Option<Self> {
+        shared
+            .connections
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < MAX_CONNECTIONS).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Self(shared.clone()))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.connections.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+fn handle_connection(slot: &ConnectionSlot, mut stream: TcpStream) {
+    let shared = &slot.0;
+    // Accepted sockets inherit non-blocking mode on some platforms
+    let configured = stream
+        .set_nonblocking(false)
+        .and_then(|_| stream.set_read_timeout(Some(REQUEST_TIMEOUT)));
+    if let Err(e) = configured {
+        log::debug!("Failed to configure remote API connection: {}", e);
+        return;
+    }
+    let request = match http::read_request(&mut stream, MAX_BODY_BYTES) {
+        Ok(request) => request,
+        Err(e) => {
+            respond(&mut stream, error_response(400, &e));
+            return;
+        }
+    };
+
+    if !is_authorized(shared, &request) {
+        respond(&mut stream, error_response(401, "Missing or invalid token"));
+        return;
+    }
+
+    let path = request.path.as_str();
+    let response = match (request.method.as_str(), path) {
+        ("GET", "/api/state") => json_response(200, &shared.engine().get_state()),
+        ("GET", "/api/cover") => cover_response(shared),
+        ("GET", "/api/events") => {
+            upgrade_websocket(shared, &request, stream);
+            return;
+        }
+        ("POST", _) if path.starts_with("/api/audio/") => {
+            let command = path.trim_start_matches("/api/audio/");
+            match std::str::from_utf8(&request.body) {
+                Ok(body) => run_audio_command(shared, command, body),
+                Err(_) => error_response(400, "Request body is not UTF-8"),
+            }
+        }
+        _ => error_response(404, "Not found"),
+    };
+    respond(&mut stream, response);
+}
+
+/// Dispatch `POST /api/audio/<command>` to the matching engine call.
+///
+/// Playback commands wait for the engine and answer with the resulting state;
+/// settings are fire-and-forget and answer `204`.
+fn run_audio_command(shared: &Shared, command: &str, body: &str) -> Response {
+    let engine = shared.engine();
+
+    let acknowledged = match command {
+        "play_track" => {
+            Some(
+                parse_args::<PlayTrackArgs>(body).map(|args| AudioCommand::PlayTrack {
+                    track: args.track,
+                    source_url: args.source_url,
+                }),
+            )
+        }
+        "pause" => Some(Ok(AudioCommand::Pause)),
+        "resume" => Some(Ok(AudioCommand::Resume)),
+        "toggle_play" => Some(Ok(AudioCommand::TogglePlay)),
+        "stop" => Some(Ok(AudioCommand::Stop)),
+        "seek" => Some(parse_args::<SeekArgs>(body).map(|args| AudioCommand::Seek(args.time_secs))),
+        "seek_percent" => Some(
+            parse_args::<SeekPercentArgs>(body).map(|args| AudioCommand::SeekPercent(args.percent)),
+        ),
+        _ => None,
+    };
+    if let Some(cmd) = acknowledged {
+        return match cmd.map(|cmd| engine.request(cmd)) {
+            Ok(Ok(state)) => json_response(200, &state),
+            Ok(Err(e)) => error_response(audio_error_status(&e), &e.to_string()),
+            Err(e) => error_response(400, &e),
+        };
+    }
+
+    let result = match command {
+        "set_volume" => parse_args::<VolumeArgs>(body).map(|args| engine.set_volume(args.volume)),
+        "get_state" => return json_response(200, &engine.get_state()),
+        _ => {
+            match command {
+                "toggle_mute" => engine.toggle_mute(),
+                "toggle_shuffle" => engine.toggle_shuffle(),
+                "cycle_repeat" => engine.cycle_repeat(),
+                _ => return error_response(404, &format!("Unknown audio command: {command}")),
+            }
+            Ok(())
+        }
+    };
+
+    match result {
+        Ok(()) => Response::new(204, Vec::new()),
+        Err(e) => error_response(400, &e),
+    }
+}
+
+/// HTTP status for an engine error: the engine itself failing is a 5xx.
+fn audio_error_status(err: &AudioError) -> u16 {
+    match err {
+        AudioError::Timeout(_) | AudioError::EngineUnavailable(_) | AudioError::Device(_) => 503,
+        AudioError::NothingLoaded | AudioError::Cancelled => 409,
+        _ => 422,
+    }
+}
+
+fn parse_args<T: for<'de> Deserialize<'de>>(body: &str) -> Result<T, String> {
+    serde_json::from_str(body).map_err(|e| format!("Invalid arguments: {e}"))
+}
+
+/// Cover art of the current track.
+///
+/// A remote `cover_url` is proxied. A local track's art only ever comes from
+/// its own file or its album folder: clients set `cover_url`, so it is never
+/// read as an arbitrary path.
+fn cover_response(shared: &Shared) -> Response {
+    let state = shared.engine().get_state();
+    let cover_url = state.current_track.and_then(|t| t.cover_url);
+
+    if let Some(TrackSource::HttpStream { url }) = cover_url.as_deref().map(TrackSource::from_url) {
+        return match reqwest::blocking::get(&url)
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.bytes())
+        {
+            Ok(data) => image_response(data.to_vec()),
+            Err(e) => error_response(502, &format!("Failed to fetch cover art: {e}")),
+        };
+    }
+    let local = match state.current_source {
+        Some(TrackSource::LocalFile { path }) => {
+            local_cover(&path, cover_url.as_deref().map(Path::new))
+        }
+        _ => None,
+    };
+    match local {
+        Some(data) => image_response(data),
+        None => error_response(404, "No cover art for the current track"),
+    }
+}
+
+fn image_response(data: Vec<u8>) -> Response {
+    let mime = image_mime(&data);
+    Response::new(200, data).with_header("Content-Type", mime)
+}
+
+/// Art for the local track at `track`: `requested` if it is an image in the
+/// track's folder, else the picture embedded in the track, else a cover file
+/// in its folder.
+fn local_cover(track: &Path, requested: Option<&Path>) -> Option<Vec<u8>> {
+    let dir = track.canonicalize().ok()?.parent()?.to_path_buf();
+    let in_album_dir = |path: &Path| {
+        path.canonicalize()
+            .is_ok_and(|p| p.parent() == Some(dir.as_path()) && is_image_file(&p))
+    };
+
+    if let Some(path) = requested.filter(|p| in_album_dir(p)) {
+        if let Ok(data) = std::fs::read(path) {
+            return Some(data);
+        }
+    }
+    if let Some(data) = embedded_cover(track) {
+        return Some(data);
+    }
+
+    let mut covers: Vec<_> = std::fs::read_dir(&dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_str()?.to_ascii_lowercase();
+            let rank = COVER_FILE_NAMES.iter().position(|name| *name == stem)?;
+            in_album_dir(&path).then_some((rank, path))
+        })
+        .collect();
+    covers.sort();
+    covers
+        .into_iter()
+        .find_map(|(_, path)| std::fs::read(path).ok())
+}
+
+fn is_image_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// The picture embedded in an audio file's tags, preferring the front cover.
+fn embedded_cover(path: &Path) -> Option<Vec<u8>> {
+    let file = File::open(path).ok()?;
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .ok()?;
+
+    // Tags ahead of the container (ID3v2) first, then the container's own
+    let mut visuals: Vec<Visual> = Vec::new();
+    if let Some(revision) = probed.metadata.get().as_ref().and_then(|m| m.current()) {
+        visuals.extend(revision.visuals().iter().cloned());
+    }
+    if let Some(revision) = probed.format.metadata().current() {
+        visuals.extend(revision.visuals().iter().cloned());
+    }
+    let front = visuals
+        .iter()
+        .position(|v| v.usage == Some(StandardVisualKey::FrontCover))
+        .unwrap_or(0);
+    (!visuals.is_empty()).then(|| visuals.swap_remove(front).data.into_vec())
+}
+
+/// Sniff an image MIME type from its magic bytes.
+fn image_mime(data: &[u8]) -> &'static str {
+    if data.starts_with(&[0x89, 0x50, 0x4E, 0x47]) {
+        "image/png"
+    } else if data.starts_with(&[0xFF, 0xD8]) {
+        "image/jpeg"
+    } else if data.starts_with(b"GIF8") {
+        "image/gif"
+    } else if data.len() > 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        "image/webp"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+/// Complete the WebSocket handshake and stream engine events until the client goes away.
+///
+/// Reads time out after `SOCKET_READ_TIMEOUT`, so between events the loop
+/// still answers pings and notices a client closing or vanishing.
+fn upgrade_websocket(shared: &Shared, request: &Request, mut stream: TcpStream) {
+    let wants_upgrade = request
+        .header("Upgrade")
+        .is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+    let Some(key) = request
+        .header("Sec-WebSocket-Key")
+        .filter(|_| wants_upgrade)
+    else {
+        respond(
+            &mut stream,
+            error_response(400, "Expected a WebSocket upgrade"),
+        );
+        return;
+    };
+
+    let accept = tungstenite::handshake::derive_accept_key(key.as_bytes());
+    let response = Response::new(101, Vec::new())
+        .with_header("Upgrade", "websocket")
+        .with_header("Connection", "Upgrade")
+        .with_header("Sec-WebSocket-Accept", accept);
+    if response.write_to(&mut stream).is_err()
+        || stream.set_read_timeout(Some(SOCKET_READ_TIMEOUT)).is_err()
+    {
+        return;
+    }
+    let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+
+    // Subscribe before sending the snapshot so no event falls in between
+    let engine = shared.engine();
+    let events = engine.subscribe();
+    let snapshot = EngineEvent::State(AudioStateEvent::from(&engine.get_state()));
+    if !send_event(&mut socket, &snapshot) {
+        return;
+    }
+
+    while !shared.shutdown.load(Ordering::Relaxed) {
+        // Pings are answered and closes acknowledged by tungstenite as part
+        // of reading; what clients send otherwise is ignored
+        match socket.read() {
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(_) => return,
+        }
+        loop {
+            match events.try_recv() {
+                Ok(event) => {
+                    if !send_event(&mut socket, &event) {
+                        return;
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    let _ = socket.close(None);
+                    let _ = socket.flush();
+                    return;
+                }
+            }
+        }
+    }
+    let _ = socket.close(None);
+    let _ = socket.flush();
+}
+
+/// Send one event frame. Returns false once the client has gone away.
+fn send_event(socket: &mut WebSocket<TcpStream>, event: &EngineEvent) -> bool {
+    match serde_json::to_string(event) {
+        Ok(json) => socket.send(Message::Text(json)).is_ok(),
+        Err(e) => {
+            log::warn!("Failed to serialize engine event: {}", e);
+            true
+        }
+    }
+}
+
+fn is_authorized(shared: &Shared, request: &Request) -> bool {
+    let from_header = request
+        .header("Authorization")
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let from_query = request
+        .query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="));
+
+    from_header
+        .or(from_query)
+        .is_some_and(|token| tokens_match(token, &shared.token))
+}
+
+/// Compare tokens without short-circuiting on the first differing byte.
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+fn json_response<T: Serialize>(status: u16, body: &T) -> Response {
+    let data = serde_json::to_vec(body).unwrap_or_default();
+    Response::new(status, data).with_header("Content-Type", "application/json")
+}
+
+fn error_response(status: u16, message: &str) -> Response {
+    json_response(
+        status,
+        &ErrorBody {
+            error: message.to_string(),
+        },
+    )
+}
+
+fn respond(stream: &mut TcpStream, response: Response) {
+    if let Err(e) = response.write_to(stream) {
+        log::debug!("Failed to send remote API response: {}", e);
+    }
+}